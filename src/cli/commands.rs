@@ -0,0 +1,121 @@
+use gumdrop::Options;
+
+#[derive(Debug, Options)]
+pub enum Command {
+    #[options(help = "print sinks, sources, their streams and cards")]
+    List(ListOptions),
+    #[options(help = "set volume of an entry (80, 80%, -6dB, or +5/-5 to change it)")]
+    SetVolume(SetVolumeOptions),
+    #[options(help = "mute an entry")]
+    Mute(TargetOptions),
+    #[options(help = "unmute an entry")]
+    Unmute(TargetOptions),
+    #[options(help = "toggle mute of an entry")]
+    Toggle(TargetOptions),
+    #[options(help = "move a stream to another sink or source")]
+    Move(MoveOptions),
+    #[options(help = "change active profile of a card")]
+    Profile(ProfileOptions),
+    #[options(help = "keep running and print a line for status bars on every volume change")]
+    Status(StatusOptions),
+    #[options(help = "save volumes, mutes, defaults, card profiles and routing of streams")]
+    SaveScene(SceneOptions),
+    #[options(help = "bring back everything saved in a scene")]
+    RestoreScene(SceneOptions),
+    #[options(help = "delete a saved scene")]
+    DeleteScene(SceneOptions),
+    #[options(help = "print names of saved scenes")]
+    Scenes(ScenesOptions),
+}
+
+// TARGET can be @DEFAULT_SINK@, @DEFAULT_SOURCE@, type:index (sink:0, sink-input:12),
+// type:name or just a name, where name is PulseAudio name of a device or the name shown in the UI
+
+#[derive(Debug, Options)]
+pub struct ListOptions {
+    #[options(help = "show this text")]
+    pub help: bool,
+
+    #[options(help = "print entries as a JSON array")]
+    pub json: bool,
+
+    #[options(help = "keep running and print a JSON line on every change")]
+    pub watch: bool,
+}
+
+#[derive(Debug, Options)]
+pub struct TargetOptions {
+    #[options(help = "show this text")]
+    pub help: bool,
+
+    #[options(free, required, help = "entry to change")]
+    pub target: String,
+}
+
+#[derive(Debug, Options)]
+pub struct SetVolumeOptions {
+    #[options(help = "show this text")]
+    pub help: bool,
+
+    #[options(free, required, help = "entry to change")]
+    pub target: String,
+
+    #[options(free, required, help = "new volume")]
+    pub value: String,
+}
+
+#[derive(Debug, Options)]
+pub struct MoveOptions {
+    #[options(help = "show this text")]
+    pub help: bool,
+
+    #[options(free, required, help = "sink input or source output to move")]
+    pub stream: String,
+
+    #[options(free, required, help = "sink or source to move it to")]
+    pub parent: String,
+}
+
+#[derive(Debug, Options)]
+pub struct ProfileOptions {
+    #[options(help = "show this text")]
+    pub help: bool,
+
+    #[options(free, required, help = "card to change")]
+    pub card: String,
+
+    #[options(free, required, help = "name or description of the profile")]
+    pub profile: String,
+}
+
+#[derive(Debug, Options)]
+pub struct StatusOptions {
+    #[options(help = "show this text")]
+    pub help: bool,
+
+    #[options(help = "follow the default source instead of the default sink")]
+    pub source: bool,
+
+    #[options(
+        help = "line template with {icon}, {volume}, {name}, {device} and {muted} (default '{icon} {volume}%')"
+    )]
+    pub format: Option<String>,
+
+    #[options(help = "print JSON with text, tooltip and class for waybar")]
+    pub waybar: bool,
+}
+
+#[derive(Debug, Options)]
+pub struct SceneOptions {
+    #[options(help = "show this text")]
+    pub help: bool,
+
+    #[options(free, required, help = "name of the scene")]
+    pub name: String,
+}
+
+#[derive(Debug, Options)]
+pub struct ScenesOptions {
+    #[options(help = "show this text")]
+    pub help: bool,
+}
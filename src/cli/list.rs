@@ -0,0 +1,115 @@
+use super::{apply_message, next_message};
+
+use crate::{
+    entry::{volume_to_percent, Entry, EntryIdentifier, EntryType},
+    models::{
+        json::{json_entries, JsonEntry, JsonEvent},
+        target::ALL_TYPES,
+        RSState,
+    },
+    Action,
+};
+
+use std::collections::HashMap;
+
+use tokio::sync::broadcast::Receiver;
+
+pub fn print_entries(state: &RSState) {
+    for entry_type in ALL_TYPES.iter() {
+        for (_, entry) in state.entries.iter_type(*entry_type) {
+            println!("{}", entry_line(state, entry));
+        }
+    }
+}
+
+pub fn print_json(state: &RSState) {
+    println!("{}", serde_json::to_string(&json_entries(state)).unwrap());
+}
+
+// prints every entry and then a line for every change until PulseAudio disconnects
+pub async fn watch(state: &mut RSState, rx: &mut Receiver<Action>) {
+    // last printed line of every entry, PulseAudio sends a lot of updates that don't change anything
+    let mut printed = HashMap::new();
+
+    print_updates(state, &mut printed);
+
+    while let Some(msg) = next_message(rx, state).await {
+        apply_message(state, &msg);
+
+        match msg {
+            Action::EntryUpdate(_, _)
+            | Action::EntryRemoved(_)
+            | Action::DefaultsUpdate(_, _)
+            // everything was loaded again, removals could have been missed
+            | Action::InitialStateLoaded => {
+                print_updates(state, &mut printed);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn print_updates(state: &RSState, printed: &mut HashMap<EntryIdentifier, String>) {
+    let removed = printed
+        .keys()
+        .filter(|ident| state.entries.get(ident).is_none())
+        .copied()
+        .collect::<Vec<_>>();
+    for ident in removed {
+        printed.remove(&ident);
+        println!(
+            "{}",
+            serde_json::to_string(&JsonEvent::removed(ident)).unwrap()
+        );
+    }
+
+    for entry_type in ALL_TYPES.iter() {
+        for (ident, entry) in state.entries.iter_type(*entry_type) {
+            let line = serde_json::to_string(&JsonEvent::Update {
+                entry: JsonEntry::new(state, entry),
+            })
+            .unwrap();
+
+            if printed.get(ident) != Some(&line) {
+                println!("{}", line);
+                printed.insert(*ident, line);
+            }
+        }
+    }
+}
+
+fn entry_line(state: &RSState, entry: &Entry) -> String {
+    let ident = format!("{}:{}", entry.entry_type.as_str(), entry.index);
+
+    if let Some(card) = &entry.card_entry {
+        let profile = match card.selected_profile {
+            Some(i) => card.profiles[i].description.as_str(),
+            None => "",
+        };
+
+        return format!("{:<20}          {} [{}]", ident, entry.name, profile);
+    }
+
+    let play = match &entry.play_entry {
+        Some(play) => play,
+        None => return format!("{:<20}          {}", ident, entry.name),
+    };
+
+    let default = if state.is_default(entry) { "*" } else { " " };
+    let mute = if play.mute { "M" } else { " " };
+    let parent = match (entry.entry_type, entry.parent) {
+        (EntryType::SinkInput, Some(p)) => format!(" -> sink:{}", p),
+        (EntryType::SourceOutput, Some(p)) => format!(" -> source:{}", p),
+        _ => String::new(),
+    };
+
+    format!(
+        "{:<20} {}{} {:>4}%  {}{}",
+        ident,
+        default,
+        mute,
+        volume_to_percent(play.volume.max()),
+        entry.name,
+        parent
+    )
+}
@@ -0,0 +1,284 @@
+mod commands;
+mod list;
+mod status;
+#[cfg(test)]
+mod tests;
+
+pub use commands::Command;
+
+use crate::{
+    config::{limit_volume, scenes},
+    entry::{change_volume_by, parse_volume, Entries, EntryIdentifier, EntryType},
+    models::{actions::statics::*, target, RSState, Scene},
+    pa, run, Action, RSError, DISPATCH, SENDERS, VARIABLES,
+};
+
+use std::ops::Deref;
+
+use pulse::volume::ChannelVolumes;
+
+use tokio::{
+    sync::{
+        broadcast::{channel, Receiver, RecvError},
+        mpsc,
+    },
+    task,
+};
+
+pub async fn run(command: Command) -> Result<(), RSError> {
+    let events = run::run_events().await;
+    task::spawn(events);
+
+    let (sx, rx) = channel(CHANNEL_CAPACITY);
+    SENDERS.register(MAIN_MESSAGE, sx).await;
+
+    let result = if let Command::Status(_) = command {
+        // status bars keep running, so like the UI it connects again after PulseAudio restarts
+        task::spawn(run::run_pa().await);
+        execute(command, rx).await
+    } else {
+        run_once(command, rx).await
+    };
+
+    DISPATCH.event(Action::ExitSignal).await;
+
+    result
+}
+
+// unlike the UI, don't retry when PulseAudio isn't available
+async fn run_once(command: Command, rx: Receiver<Action>) -> Result<(), RSError> {
+    let (pa_sx, pa_rx) = cb_channel::unbounded();
+    let (info_sx, info_rx) = mpsc::unbounded_channel();
+
+    task::spawn(async move { pa::start_async(pa_sx, info_rx).await });
+    let sync_pa = task::spawn_blocking(move || pa::start(pa_rx, info_sx));
+
+    tokio::select! {
+        r = execute(command, rx) => r,
+        r = sync_pa => match r {
+            Ok(Ok(())) => Err(RSError::PulseAudioDisconnected),
+            Ok(Err(e)) => Err(e),
+            Err(e) => Err(RSError::TaskHandleError(e)),
+        },
+    }
+}
+
+async fn execute(command: Command, mut rx: Receiver<Action>) -> Result<(), RSError> {
+    let mut state = RSState::default();
+    wait_for(&mut rx, &mut state, Action::InitialStateLoaded).await;
+
+    let play_types = target::PLAY_TYPES;
+
+    match command {
+        Command::List(opts) => {
+            if opts.watch {
+                list::watch(&mut state, &mut rx).await;
+            } else if opts.json {
+                list::print_json(&state);
+            } else {
+                list::print_entries(&state);
+            }
+            return Ok(());
+        }
+        Command::Status(opts) => {
+            status::watch(opts, &mut state, &mut rx).await;
+            return Ok(());
+        }
+        Command::SetVolume(opts) => {
+            let ident = target::find(&state, &opts.target, &play_types)?;
+            let mut vols = new_volume(current_volume(&state, ident)?, &opts.value)?;
+            if let Some(entry) = state.entries.get(&ident) {
+                vols = limit_volume(&VARIABLES.get().limits, entry, vols);
+            }
+            DISPATCH.event(Action::SetVolume(ident, vols)).await;
+        }
+        Command::Mute(opts) => {
+            let ident = target::find(&state, &opts.target, &play_types)?;
+            DISPATCH.event(Action::MuteEntry(ident, true)).await;
+        }
+        Command::Unmute(opts) => {
+            let ident = target::find(&state, &opts.target, &play_types)?;
+            DISPATCH.event(Action::MuteEntry(ident, false)).await;
+        }
+        Command::Toggle(opts) => {
+            let ident = target::find(&state, &opts.target, &play_types)?;
+            let mute = match state.entries.get(&ident) {
+                Some(entry) => entry.play_entry.as_ref().is_some_and(|p| p.mute),
+                None => false,
+            };
+            DISPATCH.event(Action::MuteEntry(ident, !mute)).await;
+        }
+        Command::Move(opts) => {
+            let ident = target::find(
+                &state,
+                &opts.stream,
+                &[EntryType::SinkInput, EntryType::SourceOutput],
+            )?;
+            let parent_type = if ident.entry_type == EntryType::SinkInput {
+                EntryType::Sink
+            } else {
+                EntryType::Source
+            };
+            let parent = target::find(&state, &opts.parent, &[parent_type])?;
+            DISPATCH
+                .event(Action::MoveEntryToParent(ident, parent))
+                .await;
+        }
+        Command::Profile(opts) => {
+            let ident = target::find(&state, &opts.card, &[EntryType::Card])?;
+            let profile = find_profile(&state, ident, &opts.profile)?;
+            DISPATCH
+                .event(Action::ChangeCardProfile(ident, profile))
+                .await;
+        }
+        Command::SaveScene(opts) => {
+            let mut saved = scenes::load()?;
+            saved.insert(opts.name, Scene::capture(&state));
+            return scenes::store(&saved);
+        }
+        Command::RestoreScene(opts) => {
+            let scene = match scenes::load()?.remove(&opts.name) {
+                Some(scene) => scene,
+                None => return Err(RSError::SceneNotFound(opts.name)),
+            };
+            state.restore_scene(scene).await;
+            wait_for(&mut rx, &mut state, Action::Synced).await;
+            state.finish_scene().await;
+        }
+        Command::DeleteScene(opts) => {
+            let mut saved = scenes::load()?;
+            if saved.remove(&opts.name).is_none() {
+                return Err(RSError::SceneNotFound(opts.name));
+            }
+            return scenes::store(&saved);
+        }
+        Command::Scenes(_) => {
+            for name in scenes::load()?.keys() {
+                println!("{}", name);
+            }
+            return Ok(());
+        }
+    }
+
+    // don't exit before PulseAudio actually handles the command
+    DISPATCH.event(Action::RequestSync).await;
+    wait_for(&mut rx, &mut state, Action::Synced).await;
+
+    Ok(())
+}
+
+async fn wait_for(rx: &mut Receiver<Action>, state: &mut RSState, action: Action) {
+    while let Some(msg) = next_message(rx, state).await {
+        // after a resync, that comes later than anything asked for before it
+        if msg == action || msg == Action::InitialStateLoaded {
+            return;
+        }
+
+        apply_message(state, &msg);
+    }
+}
+
+// InitialStateLoaded is returned after messages were lost and state was loaded again
+async fn next_message(rx: &mut Receiver<Action>, state: &mut RSState) -> Option<Action> {
+    match rx.recv().await {
+        Ok(msg) => Some(msg),
+        Err(RecvError::Lagged(_)) => resync(rx, state).await,
+        Err(RecvError::Closed) => None,
+    }
+}
+
+// messages come faster than they are handled and some of them were dropped,
+// so every entry is asked for again. Only the answer to the last request is complete
+async fn resync(rx: &mut Receiver<Action>, state: &mut RSState) -> Option<Action> {
+    let mut fresh = RSState::default();
+    let mut pending = 1;
+    DISPATCH.event(Action::RequestState).await;
+
+    loop {
+        match rx.recv().await {
+            Ok(Action::InitialStateLoaded) => {
+                pending -= 1;
+                if pending == 0 {
+                    state.entries = fresh.entries;
+                    state.default_sink = fresh.default_sink;
+                    state.default_source = fresh.default_source;
+                    return Some(Action::InitialStateLoaded);
+                }
+            }
+            Ok(msg) => apply_message(&mut fresh, &msg),
+            Err(RecvError::Lagged(_)) => {
+                fresh = RSState::default();
+                pending += 1;
+                DISPATCH.event(Action::RequestState).await;
+            }
+            Err(RecvError::Closed) => return None,
+        }
+    }
+}
+
+fn apply_message(state: &mut RSState, msg: &Action) {
+    match msg {
+        Action::EntryUpdate(ident, entry) => {
+            state.entries.insert(*ident, entry.deref().to_owned());
+        }
+        Action::EntryRemoved(ident) => {
+            state.entries.remove(ident);
+        }
+        Action::DefaultsUpdate(sink, source) => {
+            state.default_sink = sink.clone();
+            state.default_source = source.clone();
+        }
+        // everything is sent again after connecting
+        Action::PADisconnected => {
+            state.entries = Entries::default();
+            state.default_sink = None;
+            state.default_source = None;
+        }
+        _ => {}
+    }
+}
+
+fn current_volume(state: &RSState, ident: EntryIdentifier) -> Result<ChannelVolumes, RSError> {
+    match state.entries.get(&ident) {
+        Some(entry) => match &entry.play_entry {
+            Some(play) => Ok(play.volume),
+            None => Err(RSError::NoEntryError),
+        },
+        None => Err(RSError::NoEntryError),
+    }
+}
+
+// "+5" and "-5" change the volume by percentage points, anything else sets it
+fn new_volume(mut vols: ChannelVolumes, value: &str) -> Result<ChannelVolumes, RSError> {
+    let is_db = value.to_lowercase().ends_with("db");
+
+    if !is_db && (value.starts_with('+') || value.starts_with('-')) {
+        return match value.trim_end_matches('%').parse::<i16>() {
+            Ok(how_much) => Ok(change_volume_by(vols, how_much)),
+            Err(_) => Err(RSError::InvalidVolume(value.to_string())),
+        };
+    }
+
+    match parse_volume(value) {
+        Some(vol) => {
+            vols.scale(vol);
+            Ok(vols)
+        }
+        None => Err(RSError::InvalidVolume(value.to_string())),
+    }
+}
+
+fn find_profile(state: &RSState, ident: EntryIdentifier, profile: &str) -> Result<String, RSError> {
+    let card = match state.entries.get(&ident) {
+        Some(entry) => entry.card_entry.as_ref(),
+        None => None,
+    };
+
+    card.and_then(|card| {
+        card.profiles
+            .iter()
+            .find(|p| p.name == profile || p.description.eq_ignore_ascii_case(profile))
+    })
+    .map(|p| p.name.clone())
+    .ok_or_else(|| RSError::InvalidProfile(profile.to_string()))
+}
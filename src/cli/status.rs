@@ -0,0 +1,104 @@
+use super::{apply_message, commands::StatusOptions, next_message};
+
+use crate::{
+    entry::{volume_to_percent, Entry, EntryType},
+    models::{target, RSState},
+    Action,
+};
+
+use serde::Serialize;
+
+use tokio::sync::broadcast::Receiver;
+
+static DEFAULT_FORMAT: &str = "{icon} {volume}%";
+
+#[derive(Serialize)]
+struct WaybarLine {
+    text: String,
+    tooltip: String,
+    class: &'static str,
+    percentage: u32,
+}
+
+// prints a line every time the default sink (or source) changes,
+// an empty one while PulseAudio is disconnected
+pub async fn watch(opts: StatusOptions, state: &mut RSState, rx: &mut Receiver<Action>) {
+    let format = opts.format.as_deref().unwrap_or(DEFAULT_FORMAT);
+
+    let mut last_line = None;
+
+    loop {
+        let entry = default_entry(state, opts.source);
+        let line = status_line(entry, format, opts.source, opts.waybar);
+
+        if last_line.as_ref() != Some(&line) {
+            println!("{}", line);
+            last_line = Some(line);
+        }
+
+        match next_message(rx, state).await {
+            Some(msg) => apply_message(state, &msg),
+            None => return,
+        }
+    }
+}
+
+pub(super) fn default_entry(state: &RSState, source: bool) -> Option<&Entry> {
+    let (target, entry_type) = if source {
+        ("@DEFAULT_SOURCE@", EntryType::Source)
+    } else {
+        ("@DEFAULT_SINK@", EntryType::Sink)
+    };
+
+    target::find(state, target, &[entry_type])
+        .ok()
+        .and_then(|ident| state.entries.get(&ident))
+}
+
+pub(super) fn status_line(
+    entry: Option<&Entry>,
+    format: &str,
+    source: bool,
+    waybar: bool,
+) -> String {
+    let play = entry.and_then(|e| e.play_entry.as_ref().map(|p| (e, p)));
+
+    let (text, tooltip, muted, volume) = match play {
+        Some((entry, play)) => {
+            let volume = volume_to_percent(play.volume.max());
+            let text = format
+                .replace("{icon}", icon(volume, play.mute, source))
+                .replace("{volume}", &volume.to_string())
+                .replace("{name}", &entry.name)
+                .replace("{device}", play.device_name.as_deref().unwrap_or_default())
+                .replace("{muted}", if play.mute { "muted" } else { "" });
+            let tooltip = format!("{}: {}%", entry.name, volume);
+
+            (text, tooltip, play.mute, volume)
+        }
+        None => (String::new(), String::new(), false, 0),
+    };
+
+    if !waybar {
+        return text;
+    }
+
+    let line = WaybarLine {
+        text,
+        tooltip,
+        class: if muted { "muted" } else { "normal" },
+        percentage: volume,
+    };
+
+    serde_json::to_string(&line).unwrap()
+}
+
+fn icon(volume: u32, mute: bool, source: bool) -> &'static str {
+    match (source, mute, volume) {
+        (_, true, _) => "🔇",
+        (true, false, _) => "🎤",
+        (false, false, 0..=33) => "🔈",
+        (false, false, 34..=66) => "🔉",
+        (false, false, _) => "🔊",
+    }
+}
@@ -0,0 +1,151 @@
+use super::{
+    current_volume, new_volume,
+    status::{default_entry, status_line},
+};
+
+use crate::{
+    entry::{percent_to_volume, volume_to_percent, EntryIdentifier, EntryType},
+    event_loop::harness::Harness,
+    pa::fake::FakeBackend,
+    Action, RSError,
+};
+
+use pulse::volume::ChannelVolumes;
+
+fn stereo(left: i16, right: i16) -> ChannelVolumes {
+    let mut vols = ChannelVolumes::default();
+    vols.set_len(2);
+    vols.get_mut()[0] = percent_to_volume(left);
+    vols.get_mut()[1] = percent_to_volume(right);
+    vols
+}
+
+fn percents(vols: ChannelVolumes) -> Vec<u32> {
+    vols.get().iter().map(|v| volume_to_percent(*v)).collect()
+}
+
+fn set(value: &str) -> Option<Vec<u32>> {
+    new_volume(stereo(50, 50), value).ok().map(percents)
+}
+
+#[test]
+fn volume_is_set_in_percent_or_db() {
+    assert_eq!(set("80"), Some(vec![80, 80]));
+    assert_eq!(set("80%"), Some(vec![80, 80]));
+    assert_eq!(set("0dB"), Some(vec![100, 100]));
+    assert_eq!(set("-6dB"), Some(vec![79, 79]));
+    assert_eq!(set("150"), Some(vec![150, 150]));
+}
+
+#[test]
+fn volume_is_changed_by_signed_values() {
+    assert_eq!(set("+10"), Some(vec![60, 60]));
+    assert_eq!(set("-10%"), Some(vec![40, 40]));
+    assert_eq!(set("-80"), Some(vec![0, 0]));
+}
+
+#[test]
+fn volume_keeps_the_balance() {
+    let vols = new_volume(stereo(40, 80), "100").unwrap();
+    assert_eq!(percents(vols), vec![50, 100]);
+
+    let vols = new_volume(stereo(40, 80), "-20").unwrap();
+    assert_eq!(percents(vols), vec![30, 60]);
+}
+
+#[test]
+fn invalid_volume_is_rejected() {
+    for value in &["loud", "151", "+loud", "10dBs", "inf"] {
+        assert!(matches!(
+            new_volume(stereo(50, 50), value),
+            Err(RSError::InvalidVolume(_))
+        ));
+    }
+}
+
+#[tokio::test]
+async fn current_volume_needs_a_play_entry() {
+    let backend =
+        FakeBackend::default()
+            .sink(0, "Speakers", 50)
+            .card(0, "Built-in Audio", &["off"], 0);
+    let h = Harness::new(backend, 80, 24).await;
+
+    let sink = EntryIdentifier::new(EntryType::Sink, 0);
+    let card = EntryIdentifier::new(EntryType::Card, 0);
+    assert_eq!(
+        percents(current_volume(&h.state, sink).unwrap()),
+        vec![50, 50]
+    );
+    assert!(current_volume(&h.state, card).is_err());
+}
+
+fn status_backend() -> FakeBackend {
+    FakeBackend::default()
+        .sink(0, "Speakers", 50)
+        .sink(1, "Headphones", 100)
+        .source(0, "Microphone", 20)
+        .default_sink(1)
+        .default_source(0)
+}
+
+#[tokio::test]
+async fn status_fills_in_the_format() {
+    let h = Harness::new(status_backend(), 80, 24).await;
+    let entry = default_entry(&h.state, false);
+
+    assert_eq!(
+        status_line(entry, "{icon} {volume}%", false, false),
+        "🔊 100%"
+    );
+    assert_eq!(
+        status_line(entry, "{name} ({device}){muted}", false, false),
+        "Headphones (fake_sink.headphones)"
+    );
+    assert_eq!(status_line(entry, "{unknown}", false, false), "{unknown}");
+}
+
+#[tokio::test]
+async fn status_shows_muted_entries() {
+    let mut h = Harness::new(status_backend(), 80, 24).await;
+    h.send(Action::MuteEntry(
+        EntryIdentifier::new(EntryType::Sink, 1),
+        true,
+    ))
+    .await;
+    let entry = default_entry(&h.state, false);
+
+    assert_eq!(
+        status_line(entry, "{icon} {muted}", false, false),
+        "🔇 muted"
+    );
+    assert_eq!(
+        status_line(entry, "{volume}", false, true),
+        r#"{"text":"100","tooltip":"Headphones: 100%","class":"muted","percentage":100}"#
+    );
+}
+
+#[tokio::test]
+async fn status_follows_the_default_source() {
+    let h = Harness::new(status_backend(), 80, 24).await;
+    let entry = default_entry(&h.state, true);
+
+    assert_eq!(entry.map(|e| e.name.as_str()), Some("Microphone"));
+    assert_eq!(
+        status_line(entry, "{icon} {volume}%", true, false),
+        "🎤 20%"
+    );
+    assert_eq!(
+        status_line(entry, "{name}", true, true),
+        r#"{"text":"Microphone","tooltip":"Microphone: 20%","class":"normal","percentage":20}"#
+    );
+}
+
+#[test]
+fn status_is_empty_without_a_default() {
+    assert_eq!(status_line(None, "{icon} {volume}%", false, false), "");
+    assert_eq!(
+        status_line(None, "{volume}", false, true),
+        r#"{"text":"","tooltip":"","class":"normal","percentage":0}"#
+    );
+}
@@ -1,4 +1,7 @@
-use crate::{models::PageType, Action, RSError};
+use crate::{
+    models::{FadeCurve, PageType},
+    Action, RSError,
+};
 
 use std::convert::TryFrom;
 
@@ -10,6 +13,7 @@ impl ToString for Action {
             Action::ChangePage(PageType::Output) => "show_output".to_string(),
             Action::ChangePage(PageType::Input) => "show_input".to_string(),
             Action::ChangePage(PageType::Cards) => "show_cards".to_string(),
+            Action::ChangePage(PageType::Scenes) => "show_scenes".to_string(),
             Action::OpenContextMenu => "context_menu".to_string(),
             Action::ShowHelp => "help".to_string(),
             Action::InputVolumeValue => "input_volume_value".to_string(),
@@ -20,6 +24,15 @@ impl ToString for Action {
                     format!("raise_volume({})", num)
                 }
             }
+            Action::RequestFade(None, level, ms, FadeCurve::Linear) => {
+                format!("fade_to({},{})", level, ms)
+            }
+            Action::RequestFade(None, level, ms, curve) => {
+                format!("fade_to({},{},{})", level, ms, curve.as_str())
+            }
+            Action::RequestFade(Some(target), level, ms, curve) => {
+                format!("fade_to({},{},{},{})", target, level, ms, curve.as_str())
+            }
             Action::MoveUp(num) => format!("up({})", num),
             Action::MoveDown(num) => format!("down({})", num),
             Action::CyclePages(1) => "cycle_pages_forward".to_string(),
@@ -27,6 +40,14 @@ impl ToString for Action {
             Action::CloseContextMenu => "close_context_menu".to_string(),
             Action::Confirm => "confirm".to_string(),
             Action::Hide => "hide".to_string(),
+            Action::ToggleChannels => "toggle_channels".to_string(),
+            Action::ResetPeaks => "reset_peaks".to_string(),
+            Action::StartSearch => "search".to_string(),
+            Action::NextMatch => "next_match".to_string(),
+            Action::PreviousMatch => "previous_match".to_string(),
+            Action::Undo => "undo".to_string(),
+            Action::Redo => "redo".to_string(),
+            Action::ToggleDucking => "toggle_ducking".to_string(),
             _ => "".to_string(),
         }
     }
@@ -60,6 +81,7 @@ impl TryFrom<String> for Action {
             "show_output" => Action::ChangePage(PageType::Output),
             "show_input" => Action::ChangePage(PageType::Input),
             "show_cards" => Action::ChangePage(PageType::Cards),
+            "show_scenes" => Action::ChangePage(PageType::Scenes),
             "context_menu" => Action::OpenContextMenu,
             "help" => Action::ShowHelp,
             "input_volume_value" => Action::InputVolumeValue,
@@ -81,6 +103,37 @@ impl TryFrom<String> for Action {
                 };
                 Action::RequstChangeVolume(a)
             }
+            "fade_to" => {
+                // optionally the entry, then level in percent,
+                // time in milliseconds and optionally the curve
+                let mut args = a.split(',').map(|x| x.trim()).collect::<Vec<_>>();
+
+                // the entry is told apart from the level by not being a number,
+                // or by being followed by all three of the other arguments
+                let target = match args.first() {
+                    Some(first)
+                        if args.len() == 4
+                            || (args.len() == 3 && first.parse::<u16>().is_err()) =>
+                    {
+                        Some(args.remove(0).to_string())
+                    }
+                    _ => None,
+                };
+
+                let curve = match args.get(2) {
+                    Some(c) => FadeCurve::from_str(c),
+                    None => Some(FadeCurve::Linear),
+                };
+                let ms = args.get(1).and_then(|x| x.parse::<u64>().ok());
+                match (args[0].parse::<u16>(), ms, curve) {
+                    (Ok(level), Some(ms), Some(curve)) if args.len() <= 3 => {
+                        Action::RequestFade(target, level, ms, curve)
+                    }
+                    _ => {
+                        return Err(RSError::ActionBindingError(st.clone()));
+                    }
+                }
+            }
             "up" => {
                 let a = match a.parse::<u16>() {
                     Ok(x) => x,
@@ -104,6 +157,14 @@ impl TryFrom<String> for Action {
             "close_context_menu" => Action::CloseContextMenu,
             "confirm" => Action::Confirm,
             "hide" => Action::Hide,
+            "toggle_channels" => Action::ToggleChannels,
+            "reset_peaks" => Action::ResetPeaks,
+            "search" => Action::StartSearch,
+            "next_match" => Action::NextMatch,
+            "previous_match" => Action::PreviousMatch,
+            "undo" => Action::Undo,
+            "redo" => Action::Redo,
+            "toggle_ducking" => Action::ToggleDucking,
             _ => {
                 return Err(RSError::ActionBindingError(st.clone()));
             }
@@ -0,0 +1,119 @@
+use crate::{
+    entry::{parse_volume, Entry},
+    RSError,
+};
+
+use linked_hash_map::LinkedHashMap;
+
+use serde::{Deserialize, Serialize};
+
+use pulse::{
+    proplist::properties,
+    volume::{ChannelVolumes, Volume},
+};
+
+// a limit as written in the [limits] section of the config
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct LimitConfig {
+    device: Option<String>,
+    application: Option<String>,
+    binary: Option<String>,
+    role: Option<String>,
+    max_volume: Option<String>,
+}
+
+// highest volume matching entries can be set to from rsmixer
+#[derive(Clone, Debug, PartialEq)]
+pub struct Limit {
+    pub name: String,
+    // PulseAudio name of a sink or source
+    pub device: Option<String>,
+    // proplist keys of streams with values they have to be equal to (ignoring case)
+    pub matches: Vec<(&'static str, String)>,
+    pub max_volume: Volume,
+}
+
+impl Limit {
+    pub fn new(name: &str, config: &LimitConfig) -> Result<Self, RSError> {
+        let invalid = |reason: &str| RSError::InvalidLimit(name.to_string(), reason.to_string());
+
+        let matches = [
+            (properties::APPLICATION_NAME, &config.application),
+            (properties::APPLICATION_PROCESS_BINARY, &config.binary),
+            (properties::MEDIA_ROLE, &config.role),
+        ]
+        .iter()
+        .filter_map(|(key, value)| value.as_ref().map(|v| (*key, v.clone())))
+        .collect::<Vec<_>>();
+
+        match (&config.device, matches.is_empty()) {
+            (None, true) => {
+                return Err(invalid(
+                    "it needs a device, application, binary or role to match",
+                ));
+            }
+            (Some(_), false) => {
+                return Err(invalid(
+                    "a device can't be matched together with application, binary or role",
+                ));
+            }
+            _ => {}
+        }
+
+        let max_volume = match &config.max_volume {
+            Some(v) => parse_volume(v).ok_or_else(|| invalid("max_volume is not valid"))?,
+            None => return Err(invalid("max_volume is missing")),
+        };
+
+        Ok(Self {
+            name: name.to_string(),
+            device: config.device.clone(),
+            matches,
+            max_volume,
+        })
+    }
+
+    pub fn matches(&self, entry: &Entry) -> bool {
+        let play = match &entry.play_entry {
+            Some(play) => play,
+            None => return false,
+        };
+
+        self.device
+            .as_ref()
+            .is_none_or(|d| play.device_name.as_ref() == Some(d))
+            && self.matches.iter().all(|(key, value)| {
+                play.properties
+                    .get(*key)
+                    .is_some_and(|v| v.eq_ignore_ascii_case(value))
+            })
+    }
+}
+
+// volume lowered to the smallest limit of the entry, keeping the balance between channels
+pub fn limit_volume(limits: &[Limit], entry: &Entry, mut vols: ChannelVolumes) -> ChannelVolumes {
+    let max = limits
+        .iter()
+        .filter(|l| l.matches(entry))
+        .map(|l| l.max_volume)
+        .min();
+
+    if let Some(max) = max {
+        if vols.max() > max {
+            vols.scale(max);
+        }
+    }
+    vols
+}
+
+pub fn interpret(
+    limits: &Option<LinkedHashMap<String, LimitConfig>>,
+) -> Result<Vec<Limit>, RSError> {
+    match limits {
+        Some(limits) => limits
+            .iter()
+            .map(|(name, config)| Limit::new(name, config))
+            .collect(),
+        None => Ok(Vec::new()),
+    }
+}
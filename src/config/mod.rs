@@ -1,8 +1,13 @@
 mod actions;
 mod colors;
 pub mod keys;
+mod limits;
+mod rules;
+pub mod scenes;
 mod variables;
 
+pub use limits::{limit_volume, Limit, LimitConfig};
+pub use rules::{Rule, RuleConfig};
 pub use variables::Variables;
 
 use crate::{Action, RSError, Styles, VERSION};
@@ -23,8 +28,23 @@ use semver::Version;
 pub struct RsMixerConfig {
     version: Option<String>,
     pa_retry_time: Option<u64>,
+    hide_unavailable_profiles: Option<bool>,
+    meter_scale: Option<String>,
+    peak_hold_time: Option<u64>,
+    peak_decay_time: Option<u64>,
+    per_channel_peaks: Option<bool>,
+    fps: Option<u64>,
+    mouse: Option<bool>,
+    ducking: Option<bool>,
+    ducking_amount: Option<u8>,
+    ducking_roles: Option<Vec<String>>,
+    soft_mute: Option<bool>,
+    soft_mute_time: Option<u64>,
+    enforce_limits: Option<bool>,
     bindings: MultiMap<String, String>,
     colors: LinkedHashMap<String, LinkedHashMap<String, String>>,
+    rules: Option<LinkedHashMap<String, RuleConfig>>,
+    limits: Option<LinkedHashMap<String, LimitConfig>>,
 }
 
 impl RsMixerConfig {
@@ -33,6 +53,12 @@ impl RsMixerConfig {
         Ok(config)
     }
 
+    // writes back the config upgraded by interpret()
+    pub fn save(&self) -> Result<(), RSError> {
+        confy::store("rsmixer", self.clone())?;
+        Ok(())
+    }
+
     pub fn interpret(
         &mut self,
     ) -> Result<(Styles, MultiMap<KeyEvent, Action>, Variables), RSError> {
@@ -64,9 +90,7 @@ impl RsMixerConfig {
 
         self.version = Some(String::from(VERSION));
 
-        confy::store("rsmixer", self.clone())?;
-
-        Ok((styles, bindings, Variables::new(self)))
+        Ok((styles, bindings, Variables::new(self)?))
     }
 
     fn bindings(&self) -> Result<MultiMap<KeyEvent, Action>, RSError> {
@@ -145,10 +169,21 @@ impl std::default::Default for RsMixerConfig {
         bindings.insert("m".to_string(), "mute".to_string());
 
         bindings.insert("e".to_string(), "input_volume_value".to_string());
+        bindings.insert("c".to_string(), "toggle_channels".to_string());
+        bindings.insert("r".to_string(), "reset_peaks".to_string());
+
+        bindings.insert("/".to_string(), "search".to_string());
+        bindings.insert("n".to_string(), "next_match".to_string());
+        bindings.insert("shift+n".to_string(), "previous_match".to_string());
+
+        bindings.insert("u".to_string(), "undo".to_string());
+        bindings.insert("ctrl+r".to_string(), "redo".to_string());
+        bindings.insert("d".to_string(), "toggle_ducking".to_string());
 
         bindings.insert("1".to_string(), "show_output".to_string());
         bindings.insert("2".to_string(), "show_input".to_string());
         bindings.insert("3".to_string(), "show_cards".to_string());
+        bindings.insert("4".to_string(), "show_scenes".to_string());
         bindings.insert("tab".to_string(), "cycle_pages_forward".to_string());
         bindings.insert("shift+tab".to_string(), "cycle_pages_backward".to_string());
 
@@ -181,8 +216,23 @@ impl std::default::Default for RsMixerConfig {
         Self {
             version: Some(String::from(VERSION)),
             pa_retry_time: None,
+            hide_unavailable_profiles: None,
+            meter_scale: None,
+            peak_hold_time: None,
+            peak_decay_time: None,
+            per_channel_peaks: None,
+            fps: None,
+            mouse: None,
+            ducking: None,
+            ducking_amount: None,
+            ducking_roles: None,
+            soft_mute: None,
+            soft_mute_time: None,
+            enforce_limits: None,
             bindings,
             colors: styles,
+            rules: None,
+            limits: None,
         }
     }
 }
@@ -0,0 +1,92 @@
+use crate::{
+    entry::{parse_volume, Entry, EntryType},
+    RSError,
+};
+
+use linked_hash_map::LinkedHashMap;
+
+use serde::{Deserialize, Serialize};
+
+use pulse::{proplist::properties, volume::Volume};
+
+// a rule as written in the [rules] section of the config
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct RuleConfig {
+    application: Option<String>,
+    binary: Option<String>,
+    role: Option<String>,
+    volume: Option<String>,
+    mute: Option<bool>,
+    target: Option<String>,
+    call: Option<bool>,
+}
+
+// applied to every new sink input and source output with matching properties
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rule {
+    pub name: String,
+    // proplist keys with values they have to be equal to (ignoring case)
+    pub matches: Vec<(&'static str, String)>,
+    pub volume: Option<Volume>,
+    pub mute: Option<bool>,
+    // sink or source the stream is moved to, written like a cli target
+    pub target: Option<String>,
+    // matching streams lower other streams while they play, like phone calls do
+    pub call: bool,
+}
+
+impl Rule {
+    pub fn new(name: &str, config: &RuleConfig) -> Result<Self, RSError> {
+        let invalid = |reason: &str| RSError::InvalidRule(name.to_string(), reason.to_string());
+
+        let matches = [
+            (properties::APPLICATION_NAME, &config.application),
+            (properties::APPLICATION_PROCESS_BINARY, &config.binary),
+            (properties::MEDIA_ROLE, &config.role),
+        ]
+        .iter()
+        .filter_map(|(key, value)| value.as_ref().map(|v| (*key, v.clone())))
+        .collect::<Vec<_>>();
+
+        if matches.is_empty() {
+            return Err(invalid("it needs an application, binary or role to match"));
+        }
+
+        let volume = match &config.volume {
+            Some(v) => Some(parse_volume(v).ok_or_else(|| invalid("volume is not valid"))?),
+            None => None,
+        };
+
+        Ok(Self {
+            name: name.to_string(),
+            matches,
+            volume,
+            mute: config.mute,
+            target: config.target.clone(),
+            call: config.call.unwrap_or(false),
+        })
+    }
+
+    pub fn matches(&self, entry: &Entry) -> bool {
+        let play = match (&entry.entry_type, &entry.play_entry) {
+            (EntryType::SinkInput, Some(play)) | (EntryType::SourceOutput, Some(play)) => play,
+            _ => return false,
+        };
+
+        self.matches.iter().all(|(key, value)| {
+            play.properties
+                .get(*key)
+                .is_some_and(|v| v.eq_ignore_ascii_case(value))
+        })
+    }
+}
+
+pub fn interpret(rules: &Option<LinkedHashMap<String, RuleConfig>>) -> Result<Vec<Rule>, RSError> {
+    match rules {
+        Some(rules) => rules
+            .iter()
+            .map(|(name, config)| Rule::new(name, config))
+            .collect(),
+        None => Ok(Vec::new()),
+    }
+}
@@ -0,0 +1,49 @@
+use crate::{models::Scene, RSError};
+
+use std::path::PathBuf;
+
+use linked_hash_map::LinkedHashMap;
+
+// scenes are kept in their own file next to rsmixer.toml,
+// so saving one doesn't rewrite the config
+pub fn load() -> Result<LinkedHashMap<String, Scene>, RSError> {
+    Ok(confy::load_path(path()?)?)
+}
+
+pub fn store(scenes: &LinkedHashMap<String, Scene>) -> Result<(), RSError> {
+    confy::store_path(path()?, scenes)?;
+    Ok(())
+}
+
+#[cfg(not(test))]
+fn path() -> Result<PathBuf, RSError> {
+    match directories::ProjectDirs::from("rs", "", "rsmixer") {
+        Some(dirs) => Ok(dirs.config_dir().join("scenes.toml")),
+        None => Err(RSError::ConfyError(
+            confy::ConfyError::BadConfigDirectoryStr,
+        )),
+    }
+}
+
+// tests shouldn't touch scenes of whoever runs them, nor of each other.
+// Every test runs on its own thread named after it
+#[cfg(test)]
+fn path() -> Result<PathBuf, RSError> {
+    let test = std::thread::current()
+        .name()
+        .unwrap_or("main")
+        .replace("::", "-");
+
+    Ok(std::env::temp_dir().join(format!(
+        "rsmixer-scenes-{}-{}.toml",
+        std::process::id(),
+        test
+    )))
+}
+
+#[cfg(test)]
+pub fn remove() {
+    if let Ok(path) = path() {
+        let _ = std::fs::remove_file(path);
+    }
+}
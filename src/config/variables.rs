@@ -1,13 +1,63 @@
-use super::RsMixerConfig;
+use super::{limits, rules, Limit, RsMixerConfig, Rule};
+
+use crate::{entry::MeterScale, RSError};
 
 pub struct Variables {
     pub pa_retry_time: u64,
+    pub hide_unavailable_profiles: bool,
+    pub meter_scale: MeterScale,
+    // in milliseconds
+    pub peak_hold_time: u64,
+    pub peak_decay_time: u64,
+    // open monitors with the entry's channel map and show a meter per channel
+    pub per_channel_peaks: bool,
+    // how many times a second peak meters are redrawn
+    pub fps: u64,
+    // capture the mouse, which also disables selecting text in the terminal
+    pub mouse: bool,
+    // lower other sink inputs while a call is going on, can be toggled in the UI
+    pub ducking: bool,
+    // in percent of their volume
+    pub ducking_amount: u8,
+    // media.role of streams that are calls, rules can mark more of them
+    pub ducking_roles: Vec<String>,
+    // fade out before muting and fade in after unmuting, for that many milliseconds
+    pub soft_mute: bool,
+    pub soft_mute_time: u64,
+    pub rules: Vec<Rule>,
+    pub limits: Vec<Limit>,
+    // lower volumes raised above their limit by other applications
+    pub enforce_limits: bool,
 }
 
 impl Variables {
-    pub fn new(config: &RsMixerConfig) -> Self {
-        Self {
+    pub fn new(config: &RsMixerConfig) -> Result<Self, RSError> {
+        let meter_scale = match config.meter_scale.as_deref() {
+            None | Some("linear") => MeterScale::Linear,
+            Some("db") => MeterScale::Db,
+            Some(other) => return Err(RSError::InvalidMeterScale(other.to_string())),
+        };
+
+        Ok(Self {
             pa_retry_time: config.pa_retry_time.unwrap_or(5),
-        }
+            hide_unavailable_profiles: config.hide_unavailable_profiles.unwrap_or(false),
+            meter_scale,
+            peak_hold_time: config.peak_hold_time.unwrap_or(1500),
+            peak_decay_time: config.peak_decay_time.unwrap_or(1000),
+            per_channel_peaks: config.per_channel_peaks.unwrap_or(false),
+            fps: config.fps.unwrap_or(30).clamp(1, 1000),
+            mouse: config.mouse.unwrap_or(false),
+            ducking: config.ducking.unwrap_or(false),
+            ducking_amount: config.ducking_amount.unwrap_or(50).min(100),
+            ducking_roles: config
+                .ducking_roles
+                .clone()
+                .unwrap_or_else(|| vec!["phone".to_string()]),
+            soft_mute: config.soft_mute.unwrap_or(false),
+            soft_mute_time: config.soft_mute_time.unwrap_or(300),
+            rules: rules::interpret(&config.rules)?,
+            limits: limits::interpret(&config.limits)?,
+            enforce_limits: config.enforce_limits.unwrap_or(false),
+        })
     }
 }
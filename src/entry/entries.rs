@@ -1,6 +1,6 @@
 use super::{Entry, EntryIdentifier, EntryType};
 
-use std::collections::BTreeMap;
+use std::collections::{btree_map, BTreeMap};
 
 pub struct Entries(BTreeMap<EntryIdentifier, Entry>);
 
@@ -31,6 +31,9 @@ impl Entries {
                 .filter(move |(ident, _)| ident.entry_type == entry_type),
         )
     }
+    pub fn iter_mut(&mut self) -> btree_map::IterMut<'_, EntryIdentifier, Entry> {
+        self.0.iter_mut()
+    }
     pub fn get(&self, entry_ident: &EntryIdentifier) -> Option<&Entry> {
         self.0.get(entry_ident)
     }
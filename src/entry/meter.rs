@@ -0,0 +1,103 @@
+use crate::VARIABLES;
+
+use std::time::{Duration, Instant};
+
+// lowest level shown on the dBFS scale, anything quieter is an empty bar
+pub static METER_FLOOR_DB: f32 = -60.0;
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum MeterScale {
+    Linear,
+    Db,
+}
+
+impl MeterScale {
+    // position of a sample peak (1.0 is full scale) on the meter, from 0.0 to 1.0
+    pub fn position(self, peak: f32) -> f32 {
+        match self {
+            MeterScale::Linear => peak.clamp(0.0, 1.0),
+            MeterScale::Db => {
+                if peak <= 0.0 {
+                    return 0.0;
+                }
+                let db = 20.0 * peak.log10();
+                ((db - METER_FLOOR_DB) / -METER_FLOOR_DB).clamp(0.0, 1.0)
+            }
+        }
+    }
+}
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct PeakMeter {
+    // current level and the peak-hold marker, as positions on the meter
+    pub level: f32,
+    pub hold: f32,
+    // a sample reached full scale since the last reset
+    pub clipped: bool,
+    held: f32,
+    held_since: Option<Instant>,
+}
+impl Eq for PeakMeter {}
+
+impl Default for PeakMeter {
+    fn default() -> Self {
+        Self {
+            level: 0.0,
+            hold: 0.0,
+            clipped: false,
+            held: 0.0,
+            held_since: None,
+        }
+    }
+}
+
+impl PeakMeter {
+    // returns false when nothing visible changed
+    pub fn update(&mut self, peak: f32, now: Instant) -> bool {
+        let variables = VARIABLES.get();
+        let before = (self.level, self.hold, self.clipped);
+
+        self.level = variables.meter_scale.position(peak);
+        if peak >= 1.0 {
+            self.clipped = true;
+        }
+
+        let elapsed = match self.held_since {
+            Some(since) => now.saturating_duration_since(since),
+            None => Duration::from_secs(0),
+        };
+        let hold_time = Duration::from_millis(variables.peak_hold_time);
+
+        // after the hold time the marker falls down the whole meter in peak_decay_time
+        let decayed = if elapsed > hold_time && variables.peak_decay_time > 0 {
+            let falling = (elapsed - hold_time).as_millis() as f32;
+            self.held - falling / variables.peak_decay_time as f32
+        } else if elapsed > hold_time {
+            0.0
+        } else {
+            self.held
+        };
+
+        if self.level >= decayed {
+            self.held = self.level;
+            self.held_since = Some(now);
+            self.hold = self.level;
+        } else {
+            self.hold = decayed;
+        }
+
+        let after = (self.level, self.hold, self.clipped);
+        (before.0 - after.0).abs() > f32::EPSILON
+            || (before.1 - after.1).abs() > f32::EPSILON
+            || before.2 != after.2
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self {
+            level: self.level,
+            hold: self.level,
+            held: self.level,
+            ..Self::default()
+        };
+    }
+}
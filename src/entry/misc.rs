@@ -17,6 +17,30 @@ pub enum EntryType {
     Card,
 }
 
+impl EntryType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EntryType::Sink => "sink",
+            EntryType::SinkInput => "sink-input",
+            EntryType::Source => "source",
+            EntryType::SourceOutput => "source-output",
+            EntryType::Card => "card",
+        }
+    }
+}
+
+// the other way around than EntryType::as_str
+pub fn parse_entry_type(s: &str) -> Option<EntryType> {
+    match s {
+        "sink" => Some(EntryType::Sink),
+        "sink-input" => Some(EntryType::SinkInput),
+        "source" => Some(EntryType::Source),
+        "source-output" => Some(EntryType::SourceOutput),
+        "card" => Some(EntryType::Card),
+        _ => None,
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Hash, Debug)]
 pub struct EntryIdentifier {
     pub entry_type: EntryType,
@@ -64,3 +88,11 @@ impl EntryIdentifier {
         Self { entry_type, index }
     }
 }
+
+// Identifies an entry across PulseAudio reconnects, when indexes change.
+// Sinks and sources use their PulseAudio name, everything else the displayed name
+#[derive(Clone, PartialEq, Hash, Eq, Debug)]
+pub struct EntryKey {
+    pub entry_type: EntryType,
+    pub name: String,
+}
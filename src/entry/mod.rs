@@ -1,30 +1,101 @@
 mod entries;
+mod meter;
 mod misc;
+mod volume;
 
 pub use entries::Entries;
-pub use misc::{EntryIdentifier, EntrySpaceLvl, EntryType};
+pub use meter::{MeterScale, PeakMeter};
+pub use misc::{parse_entry_type, EntryIdentifier, EntryKey, EntrySpaceLvl, EntryType};
+pub use volume::{
+    change_volume_by, parse_volume, percent_to_volume, volume_to_percent, MAX_PERCENT,
+};
 
 use crate::ui::{widgets::VolumeWidget, Rect};
 
+use std::{collections::HashMap, time::Instant};
+
 #[derive(PartialEq, Clone, Debug)]
 pub struct PlayEntry {
-    pub peak: f32,
+    pub meter: PeakMeter,
     pub mute: bool,
     pub volume: pulse::volume::ChannelVolumes,
+    pub channel_map: pulse::channelmap::Map,
     pub monitor_source: Option<u32>,
     pub sink: Option<u32>,
+    // PulseAudio name of a sink/source, used to match it against server defaults
+    pub device_name: Option<String>,
+    // media name, binary, role and such of the application playing a stream,
+    // by their proplist keys
+    pub properties: HashMap<String, String>,
     pub volume_bar: VolumeWidget,
-    pub peak_volume_bar: VolumeWidget,
+    // one meter per channel, when monitors record every channel separately
+    pub channel_meters: Vec<PeakMeter>,
     pub suspended: bool,
+    // a stream paused by its application
+    pub corked: bool,
+    pub expanded: bool,
+    // ports of a sink/source, like speakers and headphones
+    pub ports: Vec<DevicePort>,
+    pub active_port: Option<usize>,
+    // None when the whole entry is selected, channel index otherwise.
+    // Index equal to the number of channels selects the balance row
+    pub selected_channel: Option<usize>,
 }
 impl Eq for PlayEntry {}
 
+impl PlayEntry {
+    // number of additional rows shown under an expanded entry
+    pub fn channel_rows(&self) -> usize {
+        let balance = if self.channel_map.can_balance() { 1 } else { 0 };
+        self.volume.len() as usize + balance
+    }
+
+    // a single value updates the mono meter, more update every channel
+    // (and the mono meter with the loudest of them). Returns false when nothing changed
+    pub fn update_peaks(&mut self, peaks: &[f32], now: Instant) -> bool {
+        let loudest = peaks.iter().cloned().fold(0.0, f32::max);
+        let mut changed = self.meter.update(loudest, now);
+
+        if peaks.len() > 1 {
+            if self.channel_meters.len() != peaks.len() {
+                self.channel_meters = vec![PeakMeter::default(); peaks.len()];
+                changed = true;
+            }
+            for (meter, peak) in self.channel_meters.iter_mut().zip(peaks) {
+                changed |= meter.update(*peak, now);
+            }
+        } else if !self.channel_meters.is_empty() {
+            self.channel_meters.clear();
+            changed = true;
+        }
+
+        changed
+    }
+
+    pub fn reset_peaks(&mut self) {
+        self.meter.reset();
+        self.channel_meters.iter_mut().for_each(PeakMeter::reset);
+    }
+}
+
+#[derive(PartialEq, Clone, Debug)]
+pub struct DevicePort {
+    pub name: String,
+    pub description: String,
+    // false when nothing is plugged into it
+    pub available: bool,
+}
+impl Eq for DevicePort {}
+
 #[derive(PartialEq, Clone, Debug)]
 pub struct CardProfile {
     pub name: String,
     pub description: String,
-    #[cfg(any(feature = "pa_v13"))]
     pub available: bool,
+    // number of sinks and sources the card has with this profile
+    pub sinks: u32,
+    pub sources: u32,
+    pub priority: u32,
 }
 impl Eq for CardProfile {}
 
@@ -68,6 +139,9 @@ pub struct Entry {
     pub index: u32,
     pub name: String,
     pub is_selected: bool,
+    pub is_default: bool,
+    // matches the search filter
+    pub is_match: bool,
     pub parent: Option<u32>,
     pub position: EntrySpaceLvl,
     pub play_entry: Option<PlayEntry>,
@@ -95,6 +169,33 @@ impl Entry {
         area
     }
 
+    // case insensitive search in the name and properties of the entry
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        let found = |s: &str| s.to_lowercase().contains(&query);
+
+        found(&self.name)
+            || match &self.play_entry {
+                Some(play) => {
+                    play.device_name.as_deref().is_some_and(found)
+                        || play.properties.values().any(|p| found(p))
+                }
+                None => false,
+            }
+    }
+
+    pub fn key(&self) -> EntryKey {
+        let device_name = self
+            .play_entry
+            .as_ref()
+            .and_then(|p| p.device_name.as_ref());
+
+        EntryKey {
+            entry_type: self.entry_type,
+            name: device_name.unwrap_or(&self.name).clone(),
+        }
+    }
+
     pub fn monitor_source(&self, entries: &Entries) -> Option<u32> {
         match self.entry_type {
             EntryType::Card => None,
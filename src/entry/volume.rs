@@ -0,0 +1,74 @@
+use pulse::volume::{ChannelVolumes, Volume, VolumeDB};
+
+// volume changes and volume bars work in percentage points,
+// where 100 is the normal volume and 150 is the highest allowed
+pub static MAX_PERCENT: i16 = 150;
+
+fn base_delta() -> f32 {
+    (Volume::NORMAL.0 as f32 - Volume::MUTED.0 as f32) / 100.0
+}
+
+pub fn volume_to_percent(vol: Volume) -> u32 {
+    ((vol.0 - Volume::MUTED.0) as f32 / base_delta()).round() as u32
+}
+
+pub fn percent_to_volume(target_percent: i16) -> Volume {
+    let base_delta = base_delta();
+
+    let target = if target_percent < 0 {
+        Volume::MUTED.0
+    } else if target_percent == 100 {
+        Volume::NORMAL.0
+    } else if target_percent >= MAX_PERCENT {
+        (Volume::NORMAL.0 as f32 * (MAX_PERCENT as f32 / 100.0)) as u32
+    } else if target_percent < 100 {
+        Volume::MUTED.0 + target_percent as u32 * base_delta as u32
+    } else {
+        Volume::NORMAL.0 + (target_percent - 100) as u32 * base_delta as u32
+    };
+
+    Volume(target)
+}
+
+// changes the loudest channel by how_much percentage points
+// and scales the rest so the balance between them is kept
+pub fn change_volume_by(mut vols: ChannelVolumes, how_much: i16) -> ChannelVolumes {
+    let current_percent = volume_to_percent(vols.max());
+    vols.scale(percent_to_volume(current_percent as i16 + how_much));
+    vols
+}
+
+// parses volume typed by the user, either in percents ("85", "85%")
+// or in decibels ("-6dB"). Returns None for anything outside of 0-150%
+pub fn parse_volume(input: &str) -> Option<Volume> {
+    let input = input.trim().to_lowercase();
+
+    if let Some(db) = input.strip_suffix("db") {
+        let db = db.trim().parse::<f64>().ok()?;
+        if !db.is_finite() {
+            return None;
+        }
+
+        let vol = Volume::from(VolumeDB(db));
+        if volume_to_percent(vol) > MAX_PERCENT as u32 {
+            return None;
+        }
+
+        return Some(vol);
+    }
+
+    let percent = input
+        .strip_suffix('%')
+        .unwrap_or(&input)
+        .trim()
+        .parse::<f32>()
+        .ok()?
+        .round();
+
+    // "nan" and "inf" parse too, but aren't volumes
+    if !percent.is_finite() || percent < 0.0 || percent > MAX_PERCENT as f32 {
+        return None;
+    }
+
+    Some(percent_to_volume(percent as i16))
+}
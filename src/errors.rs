@@ -13,6 +13,11 @@ pub enum RSError {
     ActionBindingError(String),
     InvalidColor(String),
     InvalidVersion(String),
+    InvalidMeterScale(String),
+    // name of the rule and what's wrong with it
+    InvalidRule(String, String),
+    // name of the limit and what's wrong with it
+    InvalidLimit(String, String),
 
     // UI related errors
     TerminalTooSmall,
@@ -26,6 +31,14 @@ pub enum RSError {
     ChannelError(cb_channel::SendError<PAInternal>),
     NoEntryError,
     PulseAudioDisconnected,
+
+    // CLI and control socket related errors
+    TargetNotFound(String),
+    InvalidVolume(String),
+    InvalidProfile(String),
+    InvalidAction(String),
+    NotTargetable(String),
+    SceneNotFound(String),
 }
 
 impl Error for RSError {}
@@ -61,6 +74,31 @@ impl fmt::Display for RSError {
             Self::InvalidColor(color) => {
                 write!(f, "Error in config file\n'{}' is not a valid color", color)
             }
+            Self::InvalidMeterScale(scale) => write!(
+                f,
+                "Error in config file\n'{}' is not a valid meter scale (linear or db)",
+                scale
+            ),
+            Self::InvalidRule(name, reason) => write!(
+                f,
+                "Error in config file\nrule '{}' is not valid, {}",
+                name, reason
+            ),
+            Self::InvalidLimit(name, reason) => write!(
+                f,
+                "Error in config file\nlimit '{}' is not valid, {}",
+                name, reason
+            ),
+            Self::TargetNotFound(target) => write!(f, "'{}' doesn't match any entry", target),
+            Self::InvalidVolume(volume) => write!(f, "'{}' is not a valid volume", volume),
+            Self::InvalidProfile(profile) => {
+                write!(f, "'{}' is not a profile of this card", profile)
+            }
+            Self::InvalidAction(action) => write!(f, "'{}' is not a valid action", action),
+            Self::NotTargetable(action) => {
+                write!(f, "'{}' can't be used on a chosen entry", action)
+            }
+            Self::SceneNotFound(name) => write!(f, "there is no scene named '{}'", name),
             Self::InvalidVersion(version) => {
                 write!(
                     f,
@@ -1,9 +1,10 @@
 pub use crate::{
     entry::EntryType,
-    models::{context_menus::context_menu, PageType, RSState, RedrawType, UIMode},
+    models::{
+        context_menus::{context_menu, initial_option},
+        PageType, RSState, RedrawType, UIMode,
+    },
     Action, DISPATCH,
 };
 
-pub use std::cmp::{max, min};
-
-pub use pulse::volume;
+pub use std::cmp::min;
@@ -15,15 +15,11 @@ pub async fn action_handler(msg: &Action, state: &mut RSState) -> RedrawType {
             }
         }
         Action::MoveUp(how_much) => {
-            state.selected_context =
-                max(state.selected_context as i32 - how_much as i32, 0) as usize;
+            move_selection(state, how_much, true);
             return RedrawType::ContextMenu;
         }
         Action::MoveDown(how_much) => {
-            state.selected_context = min(
-                state.selected_context + how_much as usize,
-                state.context_options.len() - 1,
-            );
+            move_selection(state, how_much, false);
             return RedrawType::ContextMenu;
         }
         Action::CloseContextMenu => {
@@ -34,10 +30,18 @@ pub async fn action_handler(msg: &Action, state: &mut RSState) -> RedrawType {
             if state.selected >= state.page_entries.len() {
                 return RedrawType::None;
             }
+            // greyed out options can't be chosen
+            let option = match state.context_options.get(state.selected_context) {
+                Some(option) if option.is_available() => option.clone(),
+                _ => {
+                    return RedrawType::None;
+                }
+            };
 
             let ans = context_menus::resolve(
                 state.page_entries.get(state.selected).unwrap(),
-                state.context_options[state.selected_context].clone(),
+                option,
+                state,
             )
             .await;
 
@@ -67,3 +71,23 @@ pub async fn action_handler(msg: &Action, state: &mut RSState) -> RedrawType {
 
     RedrawType::None
 }
+
+// moves by how_much options that can be chosen, skipping greyed out ones
+fn move_selection(state: &mut RSState, how_much: u16, up: bool) {
+    let options = &state.context_options;
+
+    for _ in 0..how_much {
+        let next = if up {
+            (0..state.selected_context)
+                .rev()
+                .find(|&i| options[i].is_available())
+        } else {
+            (state.selected_context + 1..options.len()).find(|&i| options[i].is_available())
+        };
+
+        match next {
+            Some(next) => state.selected_context = next,
+            None => break,
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use super::common::*;
+
+use crate::{
+    config::Rule,
+    entry::Entry,
+    models::{Fade, FadeCurve},
+    VARIABLES,
+};
+
+use std::time::Duration;
+
+use pulse::{proplist::properties, volume::Volume};
+
+// lowered streams are raised back over that time, so they don't come back all at once
+static RAMP_TIME: Duration = Duration::from_millis(400);
+
+pub async fn action_handler(msg: &Action, state: &mut RSState) -> RedrawType {
+    match msg {
+        Action::EntryUpdate(_, _) | Action::InitialStateLoaded => {}
+        Action::EntryRemoved(ident) => {
+            state.ducking.ducked.remove(ident);
+        }
+        Action::ToggleDucking => {
+            state.ducking.enabled = Some(!state.ducking.is_enabled());
+        }
+        _ => return RedrawType::None,
+    };
+
+    let variables = VARIABLES.get();
+    let was_active = state.ducking.active;
+    // until every entry came in after connecting, a call may look like it's over
+    if state.loaded {
+        update(
+            state,
+            &variables.ducking_roles,
+            &variables.rules,
+            variables.ducking_amount,
+        )
+        .await;
+    }
+
+    // the toggle in the header shows whether anything is lowered
+    if *msg == Action::ToggleDucking || was_active != state.ducking.active {
+        RedrawType::Full
+    } else {
+        RedrawType::None
+    }
+}
+
+// lowers sink inputs while there's a call, and brings them back after it ends
+async fn update(state: &mut RSState, roles: &[String], rules: &[Rule], amount: u8) {
+    let calls = state
+        .entries
+        .iter_type(EntryType::SinkInput)
+        .filter(|(_, e)| is_call(e, roles, rules))
+        .map(|(ident, _)| *ident)
+        .collect::<Vec<_>>();
+
+    state.ducking.active = state.ducking.is_enabled() && !calls.is_empty();
+
+    if state.ducking.active {
+        let mut lowered = Vec::new();
+        for (ident, entry) in state.entries.iter_type(EntryType::SinkInput) {
+            if calls.contains(ident) || state.ducking.ducked.contains_key(ident) {
+                continue;
+            }
+            if let Some(play) = &entry.play_entry {
+                let mut vols = play.volume;
+                let max = play.volume.max().0 as u64 * (100 - amount as u64) / 100;
+                vols.scale(Volume(max as u32));
+                lowered.push((*ident, play.volume, vols));
+            }
+        }
+
+        for (ident, before, after) in lowered {
+            state.ducking.ducked.insert(ident, (before, after));
+            DISPATCH.event(Action::SetVolume(ident, after)).await;
+        }
+    } else {
+        raise(state).await;
+    }
+}
+
+// lowered streams are raised before exiting, dispatched ahead of ExitSignal
+// so PulseAudio gets them. Their fades are finished once it gets ExitSignal
+pub async fn release(state: &mut RSState) {
+    state.ducking.active = false;
+    raise(state).await;
+}
+
+async fn raise(state: &mut RSState) {
+    for (ident, (before, after)) in state.ducking.ducked.drain() {
+        // changed during the call by the user or another client, that stays
+        let current = state
+            .entries
+            .get(&ident)
+            .and_then(|e| e.play_entry.as_ref())
+            .map(|p| p.volume);
+        if current.is_some_and(|c| c.get() == after.get()) {
+            let fade = Fade {
+                ident,
+                from: after,
+                to: before,
+                duration: RAMP_TIME,
+                curve: FadeCurve::Linear,
+                mute: false,
+            };
+            DISPATCH.event(Action::Fade(fade)).await;
+        }
+    }
+}
+
+// a call that's going on right now, paused streams don't count
+fn is_call(entry: &Entry, roles: &[String], rules: &[Rule]) -> bool {
+    let play = match &entry.play_entry {
+        Some(play) if !play.corked => play,
+        _ => return false,
+    };
+    let role = play.properties.get(properties::MEDIA_ROLE);
+
+    role.is_some_and(|role| roles.iter().any(|r| r.eq_ignore_ascii_case(role)))
+        || rules.iter().any(|r| r.call && r.matches(entry))
+}
@@ -2,7 +2,9 @@ use super::common::*;
 
 use crate::{
     entry::{EntryIdentifier, HiddenStatus},
+    models::MonitorTarget,
     ui::util::parent_child_types,
+    VARIABLES,
 };
 
 use std::collections::{HashMap, HashSet};
@@ -13,13 +15,22 @@ pub async fn action_handler(msg: &Action, state: &mut RSState) -> RedrawType {
         Action::Redraw
         | Action::EntryRemoved(_)
         | Action::EntryUpdate(_, _)
-        | Action::ChangePage(_) => {}
+        | Action::ChangePage(_)
+        | Action::SearchChanged => {}
 
         Action::Hide => {
             if let Some(selected) = state.page_entries.get(state.selected) {
                 state.entries.hide(selected);
             }
         }
+        Action::ToggleChannels => {
+            if let Some(selected) = state.page_entries.get(state.selected) {
+                if selected.entry_type != EntryType::Card && !state.expanded.remove(&selected) {
+                    state.expanded.insert(selected);
+                }
+                state.selected_channel = None;
+            }
+        }
         _ => {
             return RedrawType::None;
         }
@@ -44,12 +55,19 @@ pub async fn action_handler(msg: &Action, state: &mut RSState) -> RedrawType {
     let entries_changed = state.page_entries.set(
         state
             .current_page
-            .generate_page(&state.entries, &state.ui_mode)
+            .generate_page(&state.entries, &state.ui_mode, &state.search)
             .map(|x| *x.0)
             .collect::<Vec<EntryIdentifier>>(),
         p,
     );
 
+    let extra_heights = state
+        .page_entries
+        .iter_entries()
+        .map(|ident| state.channel_rows(ident) as u16)
+        .collect();
+    let heights_changed = state.page_entries.set_extra_heights(extra_heights);
+
     match state.ui_mode {
         UIMode::MoveEntry(ident, _) => {
             if let Some(i) = state.page_entries.iter_entries().position(|&x| x == ident) {
@@ -57,16 +75,25 @@ pub async fn action_handler(msg: &Action, state: &mut RSState) -> RedrawType {
             }
         }
         _ => {
-            if let Some(i) = state
-                .page_entries
-                .iter_entries()
-                .position(|&x| Some(x) == last_sel)
-            {
+            if let Some(i) = state.restore_selection().or_else(|| {
+                state
+                    .page_entries
+                    .iter_entries()
+                    .position(|&x| Some(x) == last_sel)
+            }) {
                 state.selected = i;
             }
         }
     };
 
+    if state.restore.as_ref().is_some_and(|r| r.is_done()) {
+        state.restore = None;
+    }
+
+    if !entries_changed && heights_changed {
+        return RedrawType::Entries;
+    }
+
     if entries_changed {
         DISPATCH
             .event(Action::CreateMonitors(
@@ -84,13 +111,22 @@ pub async fn action_handler(msg: &Action, state: &mut RSState) -> RedrawType {
     }
 }
 
-fn monitor_list(state: &mut RSState) -> HashMap<EntryIdentifier, Option<u32>> {
+fn monitor_list(state: &mut RSState) -> HashMap<EntryIdentifier, MonitorTarget> {
+    let per_channel = VARIABLES.get().per_channel_peaks;
+
     let mut monitors = HashMap::new();
     state.page_entries.iter_entries().for_each(|ident| {
         if let Some(entry) = state.entries.get(ident) {
+            let channels = match &entry.play_entry {
+                Some(play) if per_channel && play.channel_map.len() > 1 => Some(play.channel_map),
+                _ => None,
+            };
             monitors.insert(
                 EntryIdentifier::new(entry.entry_type, entry.index),
-                entry.monitor_source(&state.entries),
+                MonitorTarget {
+                    source: entry.monitor_source(&state.entries),
+                    channels,
+                },
             );
         }
     });
@@ -1,5 +1,7 @@
 use super::common::*;
 
+use crate::VARIABLES;
+
 use std::{collections::HashMap, ops::Deref};
 
 pub async fn action_handler(msg: &Action, state: &mut RSState) -> RedrawType {
@@ -9,12 +11,50 @@ pub async fn action_handler(msg: &Action, state: &mut RSState) -> RedrawType {
         }
         Action::EntryRemoved(ident) => {
             state.entries.remove(&ident);
+            state.expanded.remove(&ident);
         }
         Action::EntryUpdate(ident, entry) => {
-            state.entries.insert(ident, entry.deref().to_owned());
+            let mut entry = entry.deref().to_owned();
+            let is_new = state.entries.get(&ident).is_none();
+            // PulseAudio doesn't know which entries were hidden in the UI
+            match state.entries.get(&ident) {
+                Some(old) => {
+                    entry.hidden = old.hidden.clone();
+                    // and peaks come from monitors, not with entry updates
+                    if let (Some(play), Some(old_play)) = (&mut entry.play_entry, &old.play_entry) {
+                        play.meter = old_play.meter;
+                        play.channel_meters = old_play.channel_meters.clone();
+                    }
+                }
+                None => state.restore_entry(&mut entry),
+            }
+            state.entries.insert(ident, entry);
+
+            // streams that were already there when connecting are left as they are
+            let variables = VARIABLES.get();
+            if is_new && state.loaded {
+                super::rules::apply(&variables.rules, &variables.limits, ident, state).await;
+            } else if !is_new && variables.enforce_limits {
+                super::limits::enforce(&variables.limits, ident, state).await;
+            }
+        }
+        Action::DefaultsUpdate(sink, source) => {
+            state.default_sink = sink;
+            state.default_source = source;
+            return RedrawType::Entries;
+        }
+        Action::MoveUp(_) | Action::MoveDown(_) => {
+            // the user already chose something else
+            if let Some(restore) = &mut state.restore {
+                restore.selected = None;
+            }
         }
         Action::ChangePage(page) => {
+            if let Some(restore) = &mut state.restore {
+                restore.selected = None;
+            }
             state.current_page = page;
+            state.selected_channel = None;
             state.ui_mode = UIMode::Normal;
             return RedrawType::Full;
         }
@@ -26,9 +66,26 @@ pub async fn action_handler(msg: &Action, state: &mut RSState) -> RedrawType {
         }
         Action::PADisconnected => {
             DISPATCH.event(Action::CreateMonitors(HashMap::new())).await;
+            let restore = state.save_for_reconnect();
+            let current_page = state.current_page;
+            let search = std::mem::take(&mut state.search);
+            let ducking = state.ducking.enabled;
+
             *state = RSState::default();
+            state.current_page = current_page;
+            state.search = search;
+            state.ducking.enabled = ducking;
+            state.restore = Some(restore);
             return RedrawType::Full;
         }
+        Action::Synced => {
+            state.finish_scene().await;
+        }
+        Action::InitialStateLoaded => {
+            // entries that didn't come back by now won't be recognized later
+            state.restore = None;
+            state.loaded = true;
+        }
         Action::RetryIn(time) => {
             state.ui_mode = UIMode::RetryIn(time);
             return RedrawType::Full;
@@ -37,8 +94,28 @@ pub async fn action_handler(msg: &Action, state: &mut RSState) -> RedrawType {
             state.ui_mode = UIMode::Normal;
             return RedrawType::Full;
         }
-        Action::InputVolumeValue => {
+        Action::ResetPeaks => {
+            for (_, entry) in state.entries.iter_mut() {
+                if let Some(play) = &mut entry.play_entry {
+                    play.reset_peaks();
+                }
+            }
+            return RedrawType::Entries;
+        }
+        Action::StartSearch if state.ui_mode == UIMode::Normal => {
+            state.ui_mode = UIMode::Search;
+            return RedrawType::Full;
+        }
+        Action::SearchChanged => {
+            return RedrawType::Full;
+        }
+        Action::InputVolumeValue
+            if state.current_page != PageType::Cards
+                && state.selected < state.page_entries.len()
+                && state.ui_mode == UIMode::Normal =>
+        {
             state.ui_mode = UIMode::InputVolumeValue;
+            state.input_exact_volume = String::new();
             return RedrawType::Entries;
         }
         _ => {}
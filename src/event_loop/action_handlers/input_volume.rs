@@ -1,7 +1,18 @@
 use super::common::*;
 
+use crate::entry::parse_volume;
+
+use std::collections::HashSet;
+
+use crossterm::event::{KeyCode, KeyEvent};
+
 pub async fn action_handler(msg: &Action, state: &mut RSState) -> RedrawType {
     match msg.clone() {
+        Action::EntryRemoved(ident) if state.page_entries.get(state.selected) == Some(ident) => {
+            state.ui_mode = UIMode::Normal;
+
+            return RedrawType::Entries;
+        }
         Action::OpenContextMenu => {
             state.ui_mode = UIMode::Normal;
 
@@ -16,3 +27,62 @@ pub async fn action_handler(msg: &Action, state: &mut RSState) -> RedrawType {
     };
     RedrawType::None
 }
+
+pub async fn key_handler(key_event: KeyEvent, state: &mut RSState) -> RedrawType {
+    match key_event.code {
+        KeyCode::Esc => {
+            state.ui_mode = UIMode::Normal;
+
+            RedrawType::Entries
+        }
+        KeyCode::Enter => {
+            let target = match parse_volume(&state.input_exact_volume) {
+                Some(t) => t,
+                None => {
+                    return RedrawType::None;
+                }
+            };
+
+            state.ui_mode = UIMode::Normal;
+
+            if let Some(ident) = state.page_entries.get(state.selected) {
+                if let Some(entry) = state.entries.get(&ident) {
+                    let mut vols = entry.play_entry.as_ref().unwrap().volume;
+
+                    match state.selected_channel {
+                        Some(c) if c < vols.len() as usize => {
+                            vols.get_mut()[c] = target;
+                        }
+                        _ => {
+                            vols.scale(target);
+                        }
+                    };
+
+                    state.change(vec![Action::SetVolume(ident, vols)]).await;
+                }
+            }
+
+            RedrawType::Entries
+        }
+        KeyCode::Backspace => {
+            state.input_exact_volume.pop();
+
+            redraw_selected(state)
+        }
+        KeyCode::Char(c) => {
+            if state.input_exact_volume.chars().count() < 10 {
+                state.input_exact_volume.push(c);
+            }
+
+            redraw_selected(state)
+        }
+        _ => RedrawType::None,
+    }
+}
+
+fn redraw_selected(state: &RSState) -> RedrawType {
+    let mut affected = HashSet::new();
+    affected.insert(state.selected);
+
+    RedrawType::PartialEntries(affected)
+}
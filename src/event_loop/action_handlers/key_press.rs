@@ -11,6 +11,9 @@ pub async fn action_handler(key_event: KeyEvent, state: &mut RSState) {
         handle_conflicting_bindings(&mut actions, state);
 
         for action in actions {
+            if action == Action::ExitSignal {
+                super::ducking::release(state).await;
+            }
             DISPATCH.event(action).await;
         }
     }
@@ -0,0 +1,23 @@
+use super::common::*;
+
+use crate::{
+    config::{limit_volume, Limit},
+    entry::EntryIdentifier,
+};
+
+// lowers the volume of an entry that went above its limit, whoever raised it
+pub async fn enforce(limits: &[Limit], ident: EntryIdentifier, state: &RSState) {
+    let entry = match state.entries.get(&ident) {
+        Some(entry) => entry,
+        None => return,
+    };
+    let play = match &entry.play_entry {
+        Some(play) => play,
+        None => return,
+    };
+
+    let vols = limit_volume(limits, entry, play.volume);
+    if vols.get() != play.volume.get() {
+        DISPATCH.event(Action::SetVolume(ident, vols)).await;
+    }
+}
@@ -1,10 +1,17 @@
 mod common;
 pub mod context_menu;
+pub mod ducking;
 pub mod entries_updates;
 pub mod general;
 pub mod input_volume;
 pub mod key_press;
+pub mod limits;
+pub mod mouse;
 pub mod move_entry;
 pub mod normal;
 pub mod play_entries;
+pub mod rules;
+pub mod scenes;
 pub mod scroll;
+pub mod search;
+pub mod socket;
@@ -0,0 +1,192 @@
+use super::common::*;
+
+use crate::{
+    entry::{change_volume_by, percent_to_volume, Entry, MAX_PERCENT},
+    ui::{page::ducking_toggle, util::terminal_size, widgets::ContextMenuWidget, Rect},
+};
+
+use std::collections::HashSet;
+
+use crossterm::event::{MouseButton, MouseEvent};
+
+// percentage points changed by one notch of the scroll wheel
+static SCROLL_STEP: i16 = 5;
+
+pub async fn action_handler(msg: &Action, state: &mut RSState) -> RedrawType {
+    let event = match msg {
+        Action::MouseInput(event) => *event,
+        _ => return RedrawType::None,
+    };
+
+    match state.ui_mode {
+        UIMode::Normal => normal_handler(event, state).await,
+        UIMode::ContextMenu => context_menu_handler(event, state).await,
+        _ => RedrawType::None,
+    }
+}
+
+async fn normal_handler(event: MouseEvent, state: &mut RSState) -> RedrawType {
+    match event {
+        MouseEvent::Down(_, x, 0, _) => {
+            if let Some(page) = PageType::at_title_column(x) {
+                DISPATCH.event(Action::ChangePage(page)).await;
+            } else if terminal_size().is_ok_and(|(w, _)| ducking_toggle(state, w).0.contains(x, 0))
+            {
+                DISPATCH.event(Action::ToggleDucking).await;
+            }
+        }
+        MouseEvent::Down(button, x, y, _) => {
+            let (index, area) = match entry_at(state, x, y) {
+                Some(hit) => hit,
+                None => return RedrawType::None,
+            };
+            let redraw = select(state, index, area, y);
+
+            match button {
+                MouseButton::Left => {
+                    let bar = Entry::volume_bar_area(state.page_entries.lvls[index], area);
+                    if bar.contains(x, y) {
+                        state.dragged = state.page_entries.get(index);
+                        set_volume_at(state, index, x).await;
+                    }
+                }
+                MouseButton::Right => {
+                    DISPATCH.event(Action::OpenContextMenu).await;
+                }
+                _ => {}
+            }
+
+            return redraw;
+        }
+        MouseEvent::Drag(MouseButton::Left, x, _, _) => {
+            let index = state
+                .dragged
+                .and_then(|ident| state.page_entries.iter_entries().position(|&i| i == ident));
+            if let Some(index) = index {
+                set_volume_at(state, index, x).await;
+            }
+        }
+        MouseEvent::Up(_, _, _, _) => {
+            state.dragged = None;
+        }
+        MouseEvent::ScrollUp(x, y, _) => {
+            change_volume_at(state, x, y, SCROLL_STEP).await;
+        }
+        MouseEvent::ScrollDown(x, y, _) => {
+            change_volume_at(state, x, y, -SCROLL_STEP).await;
+        }
+        _ => {}
+    }
+    RedrawType::None
+}
+
+async fn context_menu_handler(event: MouseEvent, state: &mut RSState) -> RedrawType {
+    match event {
+        MouseEvent::Down(MouseButton::Left, x, y, _) => {
+            let ident = match state.page_entries.get(state.selected) {
+                Some(ident) => ident,
+                None => return RedrawType::None,
+            };
+            let (w, h) = match terminal_size() {
+                Ok(size) => size,
+                Err(_) => return RedrawType::None,
+            };
+            let area = Rect::new(2, 2, w.saturating_sub(4), h.saturating_sub(4));
+
+            let mut menu = ContextMenuWidget::new(ident)
+                .selected(state.selected_context)
+                .options(state.context_options.clone());
+
+            if !menu.frame(area).contains(x, y) {
+                DISPATCH.event(Action::CloseContextMenu).await;
+            } else if let Some(option) = menu
+                .option_at(area, y)
+                .filter(|&o| state.context_options[o].is_available())
+            {
+                state.selected_context = option;
+                DISPATCH.event(Action::Confirm).await;
+                return RedrawType::ContextMenu;
+            }
+        }
+        MouseEvent::ScrollUp(_, _, _) => {
+            DISPATCH.event(Action::MoveUp(1)).await;
+        }
+        MouseEvent::ScrollDown(_, _, _) => {
+            DISPATCH.event(Action::MoveDown(1)).await;
+        }
+        _ => {}
+    }
+    RedrawType::None
+}
+
+// visible entry drawn under the pointer and the area it was drawn in
+fn entry_at(state: &RSState, x: u16, y: u16) -> Option<(usize, Rect)> {
+    (0..state.page_entries.len()).find_map(|index| {
+        match state.page_entries.is_entry_visible(index, state.scroll) {
+            Ok(Some(area)) if area.contains(x, y) => Some((index, area)),
+            _ => None,
+        }
+    })
+}
+
+// clicking one of the channel rows of an expanded entry selects that channel
+fn select(state: &mut RSState, index: usize, area: Rect, y: u16) -> RedrawType {
+    let mut affected = HashSet::new();
+    affected.insert(state.selected);
+
+    let rows = match state.page_entries.get(index) {
+        Some(ident) => state.channel_rows(&ident),
+        None => 0,
+    };
+    state.selected = index;
+    state.selected_channel = (y as usize)
+        .checked_sub(area.y as usize + 3)
+        .filter(|&c| c < rows);
+
+    affected.insert(state.selected);
+    RedrawType::PartialEntries(affected)
+}
+
+// sets volume of the entry to the position of column x on its volume bar
+async fn set_volume_at(state: &mut RSState, index: usize, x: u16) {
+    let area = match state.page_entries.is_entry_visible(index, state.scroll) {
+        Ok(Some(area)) => area,
+        _ => return,
+    };
+    let bar = Entry::volume_bar_area(state.page_entries.lvls[index], area);
+    if bar.width < 3 {
+        return;
+    }
+
+    // the bar has a border on both sides, clicking a cell fills the bar up to it
+    let inner = bar.width - 2;
+    let filled = min(x.saturating_sub(bar.x), inner);
+    let percent = (filled as f32 / inner as f32 * MAX_PERCENT as f32).round() as i16;
+
+    let ident = state.page_entries.get(index).unwrap();
+    if let Some(play) = state
+        .entries
+        .get(&ident)
+        .and_then(|e| e.play_entry.as_ref())
+    {
+        let mut vols = play.volume;
+        vols.scale(percent_to_volume(percent));
+        state.change(vec![Action::SetVolume(ident, vols)]).await;
+    }
+}
+
+async fn change_volume_at(state: &mut RSState, x: u16, y: u16, how_much: i16) {
+    let ident = match entry_at(state, x, y).and_then(|(i, _)| state.page_entries.get(i)) {
+        Some(ident) => ident,
+        None => return,
+    };
+
+    if let Some(play) = state
+        .entries
+        .get(&ident)
+        .and_then(|e| e.play_entry.as_ref())
+    {
+        let vols = change_volume_by(play.volume, how_much);
+        state.change(vec![Action::SetVolume(ident, vols)]).await;
+    }
+}
@@ -2,22 +2,6 @@ use super::common::*;
 
 pub async fn action_handler(msg: &Action, state: &mut RSState) -> RedrawType {
     match msg.clone() {
-        Action::PeakVolumeUpdate(ident, peak) => {
-            if ident.entry_type == EntryType::Card {
-                return RedrawType::None;
-            }
-            if let Some(e) = state.entries.get_mut(&ident) {
-                let play = e.play_entry.as_mut().unwrap();
-                if (play.peak - peak).abs() < f32::EPSILON {
-                    return RedrawType::None;
-                }
-                play.peak = peak;
-            }
-            if state.page_entries.iter_entries().any(|&i| i == ident) {
-                return RedrawType::PeakVolume(ident);
-            }
-            RedrawType::None
-        }
         Action::MoveUp(how_much) => {
             if let UIMode::MoveEntry(_, _) = state.ui_mode {
                 if state.page_entries.entries.len() < 2 {
@@ -72,8 +56,8 @@ pub async fn action_handler(msg: &Action, state: &mut RSState) -> RedrawType {
         Action::OpenContextMenu => match state.ui_mode {
             UIMode::MoveEntry(ident, parent) => {
                 state.ui_mode = UIMode::Normal;
-                DISPATCH
-                    .event(Action::MoveEntryToParent(ident, parent))
+                state
+                    .change(vec![Action::MoveEntryToParent(ident, parent)])
                     .await;
                 RedrawType::Full
             }
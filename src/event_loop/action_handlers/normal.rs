@@ -21,25 +21,12 @@ async fn normal_handler(msg: &Action, state: &mut RSState) -> RedrawType {
                 return RedrawType::Entries;
             }
         }
-        Action::PeakVolumeUpdate(ident, peak) => {
-            if ident.entry_type == EntryType::Card {
-                return RedrawType::None;
-            }
-            if let Some(e) = state.entries.get_mut(&ident) {
-                let play = e.play_entry.as_mut().unwrap();
-                if (play.peak - peak).abs() < f32::EPSILON {
-                    return RedrawType::None;
-                }
-                play.peak = peak;
-            }
-            if state.page_entries.iter_entries().any(|&i| i == ident) {
-                return RedrawType::PeakVolume(ident);
-            }
-        }
         Action::MoveUp(how_much) => {
             let mut affected = HashSet::new();
             affected.insert(state.selected);
-            state.selected = max(state.selected as i32 - how_much as i32, 0) as usize;
+            for _ in 0..how_much {
+                move_up(state);
+            }
             affected.insert(state.selected);
 
             return RedrawType::PartialEntries(affected);
@@ -47,11 +34,25 @@ async fn normal_handler(msg: &Action, state: &mut RSState) -> RedrawType {
         Action::MoveDown(how_much) => {
             let mut affected = HashSet::new();
             affected.insert(state.selected);
-            state.selected = min(state.selected + how_much as usize, state.page_entries.len());
+            for _ in 0..how_much {
+                move_down(state);
+            }
             affected.insert(state.selected);
 
             return RedrawType::PartialEntries(affected);
         }
+        Action::NextMatch | Action::PreviousMatch => {
+            let forward = *msg == Action::NextMatch;
+            if let Some(i) = state.find_match(state.selected, forward) {
+                let mut affected = HashSet::new();
+                affected.insert(state.selected);
+                state.selected = i;
+                state.selected_channel = None;
+                affected.insert(state.selected);
+
+                return RedrawType::PartialEntries(affected);
+            }
+        }
         Action::CyclePages(which_way) => {
             DISPATCH
                 .event(Action::ChangePage(PageType::from(
@@ -68,19 +69,24 @@ async fn normal_handler(msg: &Action, state: &mut RSState) -> RedrawType {
                 {
                     state.ui_mode = UIMode::ContextMenu;
                     state.context_options = context_menu(entry);
-
-                    if entry.entry_type == EntryType::Card {
-                        if let Some(index) = entry.card_entry.as_ref().unwrap().selected_profile {
-                            state.selected_context = index;
-                        }
-                    } else {
-                        state.selected_context = 0;
-                    }
+                    state.selected_context = initial_option(entry, &state.context_options);
 
                     return RedrawType::ContextMenu;
                 }
             }
         }
+        Action::Undo => {
+            let actions = state.history.undo(&state.entries);
+            for action in actions {
+                DISPATCH.event(action).await;
+            }
+        }
+        Action::Redo => {
+            let actions = state.history.redo(&state.entries);
+            for action in actions {
+                DISPATCH.event(action).await;
+            }
+        }
         Action::ShowHelp => {
             state.ui_mode = UIMode::Help;
             return RedrawType::Help;
@@ -89,3 +95,43 @@ async fn normal_handler(msg: &Action, state: &mut RSState) -> RedrawType {
     };
     RedrawType::None
 }
+
+// moving goes through channel rows of expanded entries before leaving them
+fn move_up(state: &mut RSState) {
+    match state.selected_channel {
+        Some(0) => {
+            state.selected_channel = None;
+        }
+        Some(channel) => {
+            state.selected_channel = Some(channel - 1);
+        }
+        None => {
+            if state.selected == 0 {
+                return;
+            }
+            state.selected = min(state.selected, state.page_entries.len()) - 1;
+            state.selected_channel = match state.page_entries.get(state.selected) {
+                Some(ident) => state.channel_rows(&ident).checked_sub(1),
+                None => None,
+            };
+        }
+    }
+}
+
+fn move_down(state: &mut RSState) {
+    let rows = match state.page_entries.get(state.selected) {
+        Some(ident) => state.channel_rows(&ident),
+        None => 0,
+    };
+    let next_channel = match state.selected_channel {
+        Some(channel) => channel + 1,
+        None => 0,
+    };
+
+    if next_channel < rows {
+        state.selected_channel = Some(next_channel);
+    } else if state.selected + 1 < state.page_entries.len() {
+        state.selected += 1;
+        state.selected_channel = None;
+    }
+}
@@ -1,55 +1,85 @@
 use super::common::*;
 
+use crate::{
+    entry::{change_volume_by, percent_to_volume, volume_to_percent, EntryIdentifier},
+    models::{
+        target::{find as find_target, PLAY_TYPES},
+        Fade, FadeCurve,
+    },
+    VARIABLES,
+};
+
+use std::time::Duration;
+
+use pulse::volume::{ChannelVolumes, Volume};
+
 pub async fn action_handler(msg: &Action, state: &mut RSState) -> RedrawType {
     match msg.clone() {
         Action::RequestMute => {
             if state.selected < state.page_entries.len() {
-                let mute = match state
-                    .entries
-                    .get(&state.page_entries.get(state.selected).unwrap())
-                {
-                    Some(e) => e.play_entry.as_ref().unwrap().mute,
+                let ident = state.page_entries.get(state.selected).unwrap();
+                let (mute, volume) = match state.entries.get(&ident) {
+                    Some(e) => {
+                        let play = e.play_entry.as_ref().unwrap();
+                        (play.mute, play.volume)
+                    }
                     None => {
                         return RedrawType::None;
                     }
                 };
-                DISPATCH
-                    .event(Action::MuteEntry(
-                        state.page_entries.get(state.selected).unwrap(),
-                        !mute,
-                    ))
-                    .await;
+
+                let variables = VARIABLES.get();
+                if variables.soft_mute {
+                    let time = Duration::from_millis(variables.soft_mute_time);
+                    soft_mute(ident, volume, mute, time, state).await;
+                } else {
+                    state.change(vec![Action::MuteEntry(ident, !mute)]).await;
+                }
             }
         }
         Action::RequstChangeVolume(how_much) => {
-            if state.selected < state.page_entries.len() {
-                if let Some(entry) = state
-                    .entries
-                    .get_mut(&state.page_entries.get(state.selected).unwrap())
-                {
-                    let mut vols = entry.play_entry.as_ref().unwrap().volume;
-                    let avg = vols.avg().0;
+            if let Some(ident) = state.page_entries.get(state.selected) {
+                let rows = state.channel_rows(&ident);
+                let selected_channel = state.selected_channel.filter(|&c| c < rows);
 
-                    let base_delta = (volume::Volume::NORMAL.0 as f32 - volume::Volume::MUTED.0 as f32) / 100.0;
+                if let Some(entry) = state.entries.get(&ident) {
+                    let play = entry.play_entry.as_ref().unwrap();
+                    let mut vols = play.volume;
 
-                    let current_percent = ((avg - volume::Volume::MUTED.0) as f32 / base_delta).round() as u32;
-                    let target_percent = current_percent as i16 + how_much;
+                    match selected_channel {
+                        Some(c) if c < vols.len() as usize => {
+                            let current_percent = volume_to_percent(vols.get()[c]);
+                            vols.get_mut()[c] =
+                                percent_to_volume(current_percent as i16 + how_much);
+                        }
+                        Some(_) => {
+                            let balance = vols.get_balance(&play.channel_map);
+                            let target = balance + how_much as f32 / 100.0;
+                            vols.set_balance(&play.channel_map, target.clamp(-1.0, 1.0));
+                        }
+                        None => {
+                            vols = change_volume_by(vols, how_much);
+                        }
+                    };
 
-                    let target = if target_percent < 0 { volume::Volume::MUTED.0 }
-                        else if target_percent == 100 { volume::Volume::NORMAL.0 }
-                        else if target_percent >= 150 { (volume::Volume::NORMAL.0 as f32 * 1.5) as u32 }
-                        else if target_percent < 100 { volume::Volume::MUTED.0 + target_percent as u32 * base_delta as u32 }
-                        else { volume::Volume::NORMAL.0 + (target_percent - 100) as u32 * base_delta as u32 };
+                    state.change(vec![Action::SetVolume(ident, vols)]).await;
+                }
+            }
+        }
+        Action::RequestFade(target, level, ms, curve) => {
+            let ident = match target {
+                Some(target) => find_target(state, &target, &PLAY_TYPES).ok(),
+                None => state.page_entries.get(state.selected),
+            };
 
-                    for v in vols.get_mut() {
-                        v.0 = target;
-                    }
-                    DISPATCH
-                        .event(Action::SetVolume(
-                            state.page_entries.get(state.selected).unwrap(),
-                            vols,
-                        ))
-                        .await;
+            if let Some(ident) = ident {
+                if let Some(play) = state
+                    .entries
+                    .get(&ident)
+                    .and_then(|e| e.play_entry.as_ref())
+                {
+                    let fade = fade_to(ident, play.volume, level, ms, curve);
+                    state.change(vec![Action::Fade(fade)]).await;
                 }
             }
         }
@@ -57,3 +87,58 @@ pub async fn action_handler(msg: &Action, state: &mut RSState) -> RedrawType {
     };
     RedrawType::None
 }
+
+// keeps the balance between channels, like other volume changes
+pub fn fade_to(
+    ident: EntryIdentifier,
+    volume: ChannelVolumes,
+    level: u16,
+    ms: u64,
+    curve: FadeCurve,
+) -> Fade {
+    let mut to = volume;
+    to.scale(percent_to_volume(level.min(i16::MAX as u16) as i16));
+
+    Fade {
+        ident,
+        from: volume,
+        to,
+        duration: Duration::from_millis(ms),
+        curve,
+        mute: false,
+    }
+}
+
+// fades out and only then mutes, or unmutes while silent and fades in
+async fn soft_mute(
+    ident: EntryIdentifier,
+    volume: ChannelVolumes,
+    muted: bool,
+    time: Duration,
+    state: &mut RSState,
+) {
+    let mut silent = volume;
+    silent.scale(Volume::MUTED);
+
+    let mut fade = Fade {
+        ident,
+        from: volume,
+        to: silent,
+        duration: time,
+        curve: FadeCurve::Log,
+        mute: true,
+    };
+
+    if muted {
+        std::mem::swap(&mut fade.from, &mut fade.to);
+        fade.mute = false;
+
+        // only unmuting is undone, the volume ends up where it was
+        DISPATCH.event(Action::SetVolume(ident, silent)).await;
+        state
+            .change(vec![Action::MuteEntry(ident, false), Action::Fade(fade)])
+            .await;
+    } else {
+        state.change(vec![Action::Fade(fade)]).await;
+    }
+}
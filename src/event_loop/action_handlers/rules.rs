@@ -0,0 +1,57 @@
+use super::common::*;
+
+use crate::{
+    config::{limit_volume, Limit, Rule},
+    entry::EntryIdentifier,
+    models::target::find as find_target,
+};
+
+// sets volume, mute and sink/source of an entry seen for the first time.
+// Every matching rule is used, later ones override what earlier ones set.
+// The volume is kept under limits of the entry, whether a rule set it or not
+pub async fn apply(rules: &[Rule], limits: &[Limit], ident: EntryIdentifier, state: &RSState) {
+    let entry = match state.entries.get(&ident) {
+        Some(entry) => entry,
+        None => return,
+    };
+    let play = match &entry.play_entry {
+        Some(play) => play,
+        None => return,
+    };
+
+    let (mut volume, mut mute, mut target) = (None, None, None);
+    for rule in rules.iter().filter(|r| r.matches(entry)) {
+        volume = rule.volume.or(volume);
+        mute = rule.mute.or(mute);
+        if let Some(t) = &rule.target {
+            target = Some((rule, t));
+        }
+    }
+
+    let mut vols = play.volume;
+    if let Some(volume) = volume {
+        vols.scale(volume);
+    }
+    let vols = limit_volume(limits, entry, vols);
+    if vols.get() != play.volume.get() {
+        DISPATCH.event(Action::SetVolume(ident, vols)).await;
+    }
+    if let Some(mute) = mute.filter(|&m| m != play.mute) {
+        DISPATCH.event(Action::MuteEntry(ident, mute)).await;
+    }
+    if let Some((rule, target)) = target {
+        let parent_type = match ident.entry_type {
+            EntryType::SinkInput => EntryType::Sink,
+            _ => EntryType::Source,
+        };
+        match find_target(state, target, &[parent_type]) {
+            Ok(parent) if entry.parent != Some(parent.index) => {
+                DISPATCH
+                    .event(Action::MoveEntryToParent(ident, parent))
+                    .await;
+            }
+            Ok(_) => {}
+            Err(err) => log::warn!("rule '{}': {}", rule.name, err),
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use super::common::*;
+
+use crate::config::scenes;
+
+use std::collections::HashSet;
+
+// the Scenes page has no entries, moving around selects scenes
+// and enter restores the selected one
+pub async fn action_handler(msg: &Action, state: &mut RSState) -> RedrawType {
+    match msg {
+        Action::ChangePage(PageType::Scenes) => {
+            state.scenes = scenes::load().unwrap_or_else(|e| {
+                log::warn!("can't load scenes: {}", e);
+                Default::default()
+            });
+            state.selected_scene = min(state.selected_scene, state.scenes.len().saturating_sub(1));
+            return RedrawType::Entries;
+        }
+        Action::MoveUp(how_much) => {
+            return select(
+                state,
+                state.selected_scene.saturating_sub(*how_much as usize),
+            );
+        }
+        Action::MoveDown(how_much) => {
+            let last = state.scenes.len().saturating_sub(1);
+            return select(state, min(state.selected_scene + *how_much as usize, last));
+        }
+        Action::OpenContextMenu | Action::Confirm => {
+            if let Some(scene) = state.scenes.values().nth(state.selected_scene) {
+                state.restore_scene(scene.clone()).await;
+            }
+        }
+        _ => {}
+    };
+
+    RedrawType::None
+}
+
+fn select(state: &mut RSState, scene: usize) -> RedrawType {
+    let mut affected = HashSet::new();
+    affected.insert(state.selected_scene);
+    state.selected_scene = scene;
+    affected.insert(state.selected_scene);
+
+    RedrawType::PartialEntries(affected)
+}
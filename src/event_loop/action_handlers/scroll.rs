@@ -1,20 +1,24 @@
 use super::common::*;
 
-use crate::{ui::util::Y_PADDING, RSError};
+use crate::{
+    ui::util::{terminal_size, Y_PADDING},
+    RSError,
+};
 
 use std::cmp::{max, min};
 
 pub async fn scroll_handler(msg: &Action, state: &mut RSState) -> Result<RedrawType, RSError> {
-    let (_, term_h) = crossterm::terminal::size()?;
+    let (_, term_h) = terminal_size()?;
 
     match msg {
         Action::EntryRemoved(_)
         | Action::EntryUpdate(_, _)
         | Action::Redraw
-        | Action::ChangePage(_) => {
+        | Action::ChangePage(_)
+        | Action::SearchChanged => {
             state.page_entries.reflow_scroll(term_h - *Y_PADDING, true);
         }
-        Action::MoveUp(_) | Action::MoveDown(_) => {}
+        Action::MoveUp(_) | Action::MoveDown(_) | Action::NextMatch | Action::PreviousMatch => {}
         _ => {
             return Ok(RedrawType::None);
         }
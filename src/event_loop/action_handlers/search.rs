@@ -0,0 +1,50 @@
+use super::common::*;
+
+use crossterm::event::{KeyCode, KeyEvent};
+
+pub async fn action_handler(msg: &Action, state: &mut RSState) -> RedrawType {
+    if *msg == Action::SearchChanged {
+        // jump to a match when the selected entry doesn't match anymore
+        let selected_matches = state
+            .page_entries
+            .get(state.selected)
+            .and_then(|ident| state.entries.get(&ident))
+            .is_some_and(|e| e.matches(&state.search));
+
+        if !selected_matches {
+            if let Some(i) = state.find_match(state.selected, true) {
+                state.selected = i;
+                state.selected_channel = None;
+                return RedrawType::Entries;
+            }
+        }
+    }
+    RedrawType::None
+}
+
+// the page is filtered while typing, enter keeps the filter and esc clears it
+pub async fn key_handler(key_event: KeyEvent, state: &mut RSState) -> RedrawType {
+    match key_event.code {
+        KeyCode::Esc => {
+            state.ui_mode = UIMode::Normal;
+            state.search.clear();
+        }
+        KeyCode::Enter => {
+            state.ui_mode = UIMode::Normal;
+
+            return RedrawType::Full;
+        }
+        KeyCode::Backspace => {
+            state.search.pop();
+        }
+        KeyCode::Char(c) => {
+            state.search.push(c);
+        }
+        _ => {
+            return RedrawType::None;
+        }
+    }
+
+    DISPATCH.event(Action::SearchChanged).await;
+    RedrawType::None
+}
@@ -0,0 +1,89 @@
+use super::{common::*, play_entries::fade_to};
+
+use crate::{
+    entry::change_volume_by,
+    models::{
+        json::JsonState,
+        target::{find as find_target, PLAY_TYPES},
+    },
+    RSError,
+};
+
+use std::convert::TryFrom;
+
+pub async fn action_handler(msg: &Action, state: &mut RSState) -> RedrawType {
+    if let Action::SocketRequest(id, request) = msg {
+        let reply = match handle_request(request.trim(), state).await {
+            Ok(reply) => reply,
+            Err(err) => format!("error: {}", err),
+        };
+
+        DISPATCH.event(Action::SocketReply(*id, reply)).await;
+    }
+
+    RedrawType::None
+}
+
+// request is either "state", an action string like in the config file,
+// or an action string followed by an entry it should be used on
+async fn handle_request(request: &str, state: &mut RSState) -> Result<String, RSError> {
+    if request == "state" {
+        return Ok(serde_json::to_string(&JsonState::new(state)).unwrap());
+    }
+
+    let (action_str, target) = match request.split_once(char::is_whitespace) {
+        Some((action_str, target)) => (action_str, Some(target.trim())),
+        None => (request, None),
+    };
+
+    let action = match Action::try_from(action_str.to_string()) {
+        Ok(action) => action,
+        Err(_) => {
+            return Err(RSError::InvalidAction(action_str.to_string()));
+        }
+    };
+
+    match target {
+        Some(target) => {
+            let action = targeted_action(action, action_str, target, state)?;
+            state.change(vec![action]).await;
+        }
+        None => {
+            if action == Action::ExitSignal {
+                super::ducking::release(state).await;
+            }
+            DISPATCH.event(action).await;
+        }
+    };
+
+    Ok("ok".to_string())
+}
+
+// actions with a target don't touch the UI, they go straight to PulseAudio
+fn targeted_action(
+    action: Action,
+    action_str: &str,
+    target: &str,
+    state: &RSState,
+) -> Result<Action, RSError> {
+    let ident = find_target(state, target, &PLAY_TYPES)?;
+    let play = match state.entries.get(&ident) {
+        Some(entry) => entry.play_entry.as_ref().ok_or(RSError::NoEntryError)?,
+        None => {
+            return Err(RSError::TargetNotFound(target.to_string()));
+        }
+    };
+
+    match action {
+        Action::RequestMute => Ok(Action::MuteEntry(ident, !play.mute)),
+        Action::RequstChangeVolume(how_much) => Ok(Action::SetVolume(
+            ident,
+            change_volume_by(play.volume, how_much),
+        )),
+        // the entry given after the action is used instead of the one inside it
+        Action::RequestFade(_, level, ms, curve) => {
+            Ok(Action::Fade(fade_to(ident, play.volume, level, ms, curve)))
+        }
+        _ => Err(RSError::NotTargetable(action_str.to_string())),
+    }
+}
@@ -0,0 +1,219 @@
+use super::{action_handlers::scroll, handle_action, handle_key, peaks_frame};
+
+use crate::{
+    config::RsMixerConfig,
+    entry::{volume_to_percent, EntryIdentifier, PeakMeter},
+    models::{
+        actions::statics::{EXIT_MESSAGE_ID, MAIN_MESSAGE, PA_MESSAGE},
+        RSState,
+    },
+    pa::{backend::Backend, fake::FakeBackend, Fades},
+    ui::util::set_terminal_size,
+    Action, BINDINGS, DISPATCH, PEAKS, STYLES, VARIABLES,
+};
+
+use std::{
+    sync::Once,
+    time::{Duration, Instant},
+};
+
+use ev_apple::Message;
+
+use lazy_static::lazy_static;
+
+use tokio::sync::{
+    broadcast::{self, Receiver},
+    Mutex, MutexGuard,
+};
+
+lazy_static! {
+    // DISPATCH is global, so tests using it can't run at the same time
+    static ref DISPATCH_LOCK: Mutex<()> = Mutex::new(());
+}
+
+static LOAD_CONFIG: Once = Once::new();
+
+// added to the default config, streams of fixtures only match it
+// when a test gives them one of these binaries
+const TEST_CONFIG: &str = "
+enforce_limits = true
+hide_unavailable_profiles = true
+
+[rules.ruled]
+binary = 'ruled'
+volume = '40%'
+mute = true
+
+[limits.limited]
+binary = 'limited'
+max_volume = '50%'
+";
+
+// Runs action handlers against a FakeBackend. Actions dispatched by the handlers
+// are delivered to the handlers or to the backend, just like on the real event bus
+pub struct Harness {
+    pub state: RSState,
+    pub backend: FakeBackend,
+    // what the control socket would send back to its clients
+    pub replies: Vec<(u64, String)>,
+    fades: Fades,
+    // nothing is handled after ExitSignal, like in the real event loop
+    exited: bool,
+    rx: Receiver<Action>,
+    _lock: MutexGuard<'static, ()>,
+}
+
+impl Harness {
+    // terminal size is only set for the current thread, so keep to #[tokio::test]
+    pub async fn new(mut backend: FakeBackend, width: u16, height: u16) -> Self {
+        let lock = DISPATCH_LOCK.lock().await;
+
+        // default config, the user's one could change what tests see
+        LOAD_CONFIG.call_once(|| {
+            let mut config = toml::Value::try_from(RsMixerConfig::default()).unwrap();
+            if let (Some(config), toml::Value::Table(test)) =
+                (config.as_table_mut(), toml::from_str(TEST_CONFIG).unwrap())
+            {
+                config.extend(test);
+            }
+            let (styles, bindings, variables) = config
+                .try_into::<RsMixerConfig>()
+                .unwrap()
+                .interpret()
+                .unwrap();
+            STYLES.set(styles);
+            BINDINGS.set(bindings);
+            VARIABLES.set(variables);
+        });
+        set_terminal_size(width, height);
+        PEAKS.clear();
+
+        let (sx, rx) = broadcast::channel(256);
+        let (sync_sx, _) = cb_channel::unbounded();
+        DISPATCH.register(sx, sync_sx).await;
+
+        backend.connect().unwrap();
+
+        let mut harness = Self {
+            state: RSState::default(),
+            backend,
+            replies: Vec::new(),
+            fades: Fades::default(),
+            exited: false,
+            rx,
+            _lock: lock,
+        };
+        harness.settle().await;
+
+        harness
+    }
+
+    // the server goes away and comes back as `backend`
+    pub async fn reconnect(&mut self, mut backend: FakeBackend) {
+        self.send(Action::PADisconnected).await;
+
+        backend.connect().unwrap();
+        self.backend = backend;
+        self.send(Action::ConnectToPA).await;
+    }
+
+    // handles the action and everything that happens because of it
+    pub async fn send(&mut self, action: Action) {
+        self.route(action).await;
+        self.settle().await;
+    }
+
+    pub async fn settle(&mut self) {
+        loop {
+            let mut pending = self.backend.take_events();
+            while let Ok(action) = self.rx.try_recv() {
+                pending.push(action);
+            }
+
+            if pending.is_empty() {
+                // a render tick, after everything else was handled
+                peaks_frame(&mut self.state).apply(&mut self.state.redraw);
+                break;
+            }
+
+            for action in pending {
+                self.route(action).await;
+            }
+        }
+    }
+
+    async fn route(&mut self, action: Action) {
+        let id = action.id();
+        if self.exited {
+            return;
+        }
+
+        if let Action::KeyPress(key_event) = action {
+            handle_key(key_event, &mut self.state).await;
+        } else if let Action::SocketReply(id, reply) = action {
+            self.replies.push((id, reply));
+        } else if id == MAIN_MESSAGE {
+            handle_action(&action, &mut self.state).await;
+            scroll::scroll_handler(&action, &mut self.state)
+                .await
+                .unwrap()
+                .apply(&mut self.state.redraw);
+        } else if id == EXIT_MESSAGE_ID {
+            for cmd in self.fades.finish() {
+                self.backend.handle_command(cmd).unwrap();
+            }
+            self.exited = true;
+        } else if id == PA_MESSAGE && !self.fades.handle(&action) {
+            match action {
+                Action::CreateMonitors(targets) => self.backend.set_monitors(targets),
+                action => self.backend.handle_command(action),
+            }
+            .unwrap();
+        }
+    }
+
+    // fades go on as if that much time passed since they started
+    pub async fn advance(&mut self, time: Duration) {
+        for cmd in self.fades.step(Instant::now() + time) {
+            self.backend.handle_command(cmd).unwrap();
+        }
+        self.settle().await;
+    }
+
+    // sends a line like a control socket client would and returns the reply
+    pub async fn request(&mut self, request: &str) -> String {
+        let id = self.replies.len() as u64;
+        self.send(Action::SocketRequest(id, request.to_string()))
+            .await;
+
+        match self.replies.iter().find(|(i, _)| *i == id) {
+            Some((_, reply)) => reply.clone(),
+            None => String::new(),
+        }
+    }
+
+    pub fn selected(&self) -> Option<EntryIdentifier> {
+        self.state.page_entries.get(self.state.selected)
+    }
+
+    pub fn volume(&self, ident: EntryIdentifier) -> u32 {
+        let entry = self.state.entries.get(&ident).unwrap();
+        volume_to_percent(entry.play_entry.as_ref().unwrap().volume.max())
+    }
+
+    pub fn channels(&self, ident: EntryIdentifier) -> Vec<u32> {
+        let entry = self.state.entries.get(&ident).unwrap();
+        let volume = entry.play_entry.as_ref().unwrap().volume;
+        volume.get().iter().map(|v| volume_to_percent(*v)).collect()
+    }
+
+    pub fn muted(&self, ident: EntryIdentifier) -> bool {
+        let entry = self.state.entries.get(&ident).unwrap();
+        entry.play_entry.as_ref().unwrap().mute
+    }
+
+    pub fn meter(&self, ident: EntryIdentifier) -> PeakMeter {
+        let entry = self.state.entries.get(&ident).unwrap();
+        entry.play_entry.as_ref().unwrap().meter
+    }
+}
@@ -1,13 +1,24 @@
 mod action_handlers;
+#[cfg(test)]
+pub mod harness;
+#[cfg(test)]
+mod tests;
 
 use action_handlers::*;
 
 use crate::{
-    models::{RSState, RedrawType, UIMode},
-    ui, Action, RSError,
+    models::{PageType, RSState, RedrawType, UIMode},
+    ui, Action, RSError, PEAKS, VARIABLES,
 };
 
-use tokio::{stream::StreamExt, sync::broadcast::Receiver};
+use std::{
+    collections::HashSet,
+    time::{Duration, Instant},
+};
+
+use tokio::{stream::StreamExt, sync::broadcast::Receiver, time};
+
+use crossterm::event::KeyEvent;
 
 pub async fn event_loop(mut rx: Receiver<Action>) -> Result<(), RSError> {
     let mut stdout = ui::prepare_terminal()?;
@@ -16,8 +27,21 @@ pub async fn event_loop(mut rx: Receiver<Action>) -> Result<(), RSError> {
 
     ui::draw_page(&mut stdout, &mut state).await?;
 
-    while let Some(Ok(msg)) = rx.next().await {
-        // run action handlers which will decide what to redraw
+    // peaks don't go through actions, they're picked up once every frame
+    let mut frames = time::interval(Duration::from_millis(1000 / VARIABLES.get().fps));
+
+    loop {
+        let msg = tokio::select! {
+            msg = rx.next() => match msg {
+                Some(Ok(msg)) => msg,
+                _ => break,
+            },
+            _ = frames.tick() => {
+                state.redraw = peaks_frame(&mut state);
+                ui::redraw(&mut stdout, &mut state).await?;
+                continue;
+            }
+        };
 
         log::debug!("Action: {:#?}", msg);
 
@@ -26,52 +50,115 @@ pub async fn event_loop(mut rx: Receiver<Action>) -> Result<(), RSError> {
                 break;
             }
             Action::KeyPress(key_event) => {
-                key_press::action_handler(key_event, &mut state).await;
+                if handle_key(key_event, &mut state).await {
+                    ui::redraw(&mut stdout, &mut state).await?;
+                }
                 continue;
             }
             _ => {}
         }
 
-        state.redraw = general::action_handler(&msg, &mut state).await;
+        handle_action(&msg, &mut state).await;
 
-        entries_updates::action_handler(&msg, &mut state)
-            .await
+        scroll::scroll_handler(&msg, &mut state)
+            .await?
             .apply(&mut state.redraw);
 
-        match state.ui_mode {
-            UIMode::Normal => {
-                normal::action_handler(&msg, &mut state)
-                    .await
-                    .apply(&mut state.redraw);
-            }
-            UIMode::ContextMenu => {
-                context_menu::action_handler(&msg, &mut state)
-                    .await
-                    .apply(&mut state.redraw);
-            }
-            UIMode::Help => {
-                if msg == Action::Redraw {
-                    state.redraw.take_bigger(RedrawType::Help);
-                }
-            }
-            UIMode::MoveEntry(_, _) => {
-                move_entry::action_handler(&msg, &mut state)
-                    .await
-                    .apply(&mut state.redraw);
-            }
-            UIMode::InputVolumeValue => {
-                input_volume::action_handler(&msg, &mut state)
+        ui::redraw(&mut stdout, &mut state).await?;
+    }
+    Ok(())
+}
+
+// while typing the volume or a search query keys are not translated into actions,
+// returns true when the key was handled right away and the screen has to be redrawn
+pub async fn handle_key(key_event: KeyEvent, state: &mut RSState) -> bool {
+    state.redraw = match state.ui_mode {
+        UIMode::InputVolumeValue => input_volume::key_handler(key_event, state).await,
+        UIMode::Search => search::key_handler(key_event, state).await,
+        _ => {
+            key_press::action_handler(key_event, state).await;
+            return false;
+        }
+    };
+    true
+}
+
+// runs action handlers which will decide what to redraw
+// (scrolling is left to the caller, because it depends on the terminal size)
+pub async fn handle_action(msg: &Action, state: &mut RSState) {
+    state.redraw = general::action_handler(msg, state).await;
+
+    socket::action_handler(msg, state)
+        .await
+        .apply(&mut state.redraw);
+
+    entries_updates::action_handler(msg, state)
+        .await
+        .apply(&mut state.redraw);
+
+    ducking::action_handler(msg, state)
+        .await
+        .apply(&mut state.redraw);
+
+    mouse::action_handler(msg, state)
+        .await
+        .apply(&mut state.redraw);
+
+    match state.ui_mode {
+        UIMode::Normal => {
+            normal::action_handler(msg, state)
+                .await
+                .apply(&mut state.redraw);
+
+            if state.current_page == PageType::Scenes {
+                scenes::action_handler(msg, state)
                     .await
                     .apply(&mut state.redraw);
             }
-            _ => {}
-        };
+        }
+        UIMode::ContextMenu => {
+            context_menu::action_handler(msg, state)
+                .await
+                .apply(&mut state.redraw);
+        }
+        UIMode::Help if *msg == Action::Redraw => {
+            state.redraw.take_bigger(RedrawType::Help);
+        }
+        UIMode::MoveEntry(_, _) => {
+            move_entry::action_handler(msg, state)
+                .await
+                .apply(&mut state.redraw);
+        }
+        UIMode::InputVolumeValue => {
+            input_volume::action_handler(msg, state)
+                .await
+                .apply(&mut state.redraw);
+        }
+        UIMode::Search => {
+            search::action_handler(msg, state)
+                .await
+                .apply(&mut state.redraw);
+        }
+        _ => {}
+    };
+}
 
-        scroll::scroll_handler(&msg, &mut state)
-            .await?
-            .apply(&mut state.redraw);
+// moves peaks written by monitors since the last frame into entries,
+// only their peak rows are redrawn
+pub fn peaks_frame(state: &mut RSState) -> RedrawType {
+    let now = Instant::now();
 
-        ui::redraw(&mut stdout, &mut state).await?;
+    let changed = PEAKS
+        .take_changed()
+        .into_iter()
+        .filter(|(ident, peaks)| state.update_peaks(*ident, peaks, now))
+        .map(|(ident, _)| ident)
+        .collect::<HashSet<_>>();
+
+    match state.ui_mode {
+        UIMode::Normal | UIMode::MoveEntry(_, _) if !changed.is_empty() => {
+            RedrawType::PeakVolumes(changed)
+        }
+        _ => RedrawType::None,
     }
-    Ok(())
 }
@@ -0,0 +1,1243 @@
+use super::{
+    action_handlers::{limits, rules},
+    harness::Harness,
+};
+
+use crate::{
+    config::{scenes, Limit, LimitConfig, Rule, RuleConfig},
+    entry::{percent_to_volume, volume_to_percent, EntryIdentifier, EntryType, HiddenStatus},
+    models::{FadeCurve, PageType, RedrawType, Scene, UIMode},
+    pa::fake::FakeBackend,
+    Action, RSError,
+};
+
+use std::{convert::TryFrom, time::Duration};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent};
+
+fn sink(index: u32) -> EntryIdentifier {
+    EntryIdentifier::new(EntryType::Sink, index)
+}
+
+fn sink_input(index: u32) -> EntryIdentifier {
+    EntryIdentifier::new(EntryType::SinkInput, index)
+}
+
+fn source(index: u32) -> EntryIdentifier {
+    EntryIdentifier::new(EntryType::Source, index)
+}
+
+fn source_output(index: u32) -> EntryIdentifier {
+    EntryIdentifier::new(EntryType::SourceOutput, index)
+}
+
+fn backend() -> FakeBackend {
+    FakeBackend::default()
+        .sink(0, "Speakers", 50)
+        .sink_input(5, "Firefox", 0, 80)
+        .sink(1, "Headphones", 100)
+        .source(0, "Microphone", 100)
+        .source(1, "Webcam", 100)
+        .source_output(7, "Recorder", 1, 100)
+        .card(0, "Built-in Audio", &["output:analog-stereo", "off"], 0)
+        .default_sink(0)
+        .default_source(1)
+}
+
+#[tokio::test]
+async fn initial_state_fills_output_page() {
+    let h = Harness::new(backend(), 80, 24).await;
+
+    let page = h
+        .state
+        .page_entries
+        .iter_entries()
+        .copied()
+        .collect::<Vec<_>>();
+    assert_eq!(page, vec![sink(0), sink_input(5), sink(1)]);
+    assert_eq!(h.selected(), Some(sink(0)));
+
+    let speakers = h.state.entries.get(&sink(0)).unwrap();
+    assert!(h.state.is_default(speakers));
+}
+
+#[tokio::test]
+async fn input_page_shows_sources() {
+    let mut h = Harness::new(backend(), 80, 24).await;
+
+    h.send(Action::ChangePage(PageType::Input)).await;
+
+    let page = h
+        .state
+        .page_entries
+        .iter_entries()
+        .copied()
+        .collect::<Vec<_>>();
+    assert_eq!(page, vec![source(0), source(1), source_output(7)]);
+
+    let webcam = h.state.entries.get(&source(1)).unwrap();
+    assert!(h.state.is_default(webcam));
+}
+
+#[tokio::test]
+async fn entries_on_page_are_monitored() {
+    let mut h = Harness::new(backend(), 80, 24).await;
+
+    assert!(h.backend.is_monitored(sink(0)));
+    assert!(h.backend.is_monitored(sink_input(5)));
+
+    h.send(Action::ChangePage(PageType::Cards)).await;
+
+    assert!(!h.backend.is_monitored(sink(0)));
+    assert_eq!(h.state.page_entries.len(), 1);
+}
+
+#[tokio::test]
+async fn moving_down_selects_next_entry() {
+    let mut h = Harness::new(backend(), 80, 24).await;
+
+    h.send(Action::MoveDown(1)).await;
+    assert_eq!(h.selected(), Some(sink_input(5)));
+
+    h.send(Action::MoveDown(5)).await;
+    assert_eq!(h.selected(), Some(sink(1)));
+
+    h.send(Action::MoveUp(1)).await;
+    assert_eq!(h.selected(), Some(sink_input(5)));
+}
+
+#[tokio::test]
+async fn changing_volume_changes_selected_entry() {
+    let mut h = Harness::new(backend(), 80, 24).await;
+
+    h.send(Action::MoveDown(1)).await;
+    h.send(Action::RequstChangeVolume(-30)).await;
+
+    assert_eq!(h.volume(sink_input(5)), 50);
+    assert_eq!(h.volume(sink(0)), 50);
+}
+
+#[tokio::test]
+async fn changing_volume_of_selected_channel_changes_only_it() {
+    let mut h = Harness::new(backend(), 80, 24).await;
+
+    h.send(Action::ToggleChannels).await;
+    h.send(Action::MoveDown(2)).await;
+    h.send(Action::RequstChangeVolume(-20)).await;
+
+    assert_eq!(h.channels(sink(0)), vec![50, 30]);
+}
+
+#[tokio::test]
+async fn balance_row_shifts_volume_between_channels() {
+    let mut h = Harness::new(backend(), 80, 24).await;
+
+    h.send(Action::ToggleChannels).await;
+    h.send(Action::MoveDown(3)).await;
+    assert_eq!(h.state.selected_channel, Some(2));
+
+    h.send(Action::RequstChangeVolume(50)).await;
+    let right = h.channels(sink(0));
+    assert_eq!(right[1], 50);
+    assert!(right[0] < 50);
+
+    h.send(Action::RequstChangeVolume(-100)).await;
+    let left = h.channels(sink(0));
+    assert_eq!(left[0], 50);
+    assert!(left[1] < 50);
+}
+
+#[tokio::test]
+async fn moving_goes_through_channel_rows() {
+    let mut h = Harness::new(backend(), 80, 24).await;
+
+    h.send(Action::ToggleChannels).await;
+    h.send(Action::MoveDown(1)).await;
+    assert_eq!(h.selected(), Some(sink(0)));
+    assert_eq!(h.state.selected_channel, Some(0));
+
+    // left, right and balance, then the next entry
+    h.send(Action::MoveDown(3)).await;
+    assert_eq!(h.selected(), Some(sink_input(5)));
+    assert_eq!(h.state.selected_channel, None);
+
+    // coming back up starts at the last row
+    h.send(Action::MoveUp(1)).await;
+    assert_eq!(h.selected(), Some(sink(0)));
+    assert_eq!(h.state.selected_channel, Some(2));
+
+    h.send(Action::MoveUp(3)).await;
+    assert_eq!(h.selected(), Some(sink(0)));
+    assert_eq!(h.state.selected_channel, None);
+}
+
+async fn type_volume(h: &mut Harness, value: &str) {
+    h.send(Action::InputVolumeValue).await;
+    for c in value.chars() {
+        h.send(key(KeyCode::Char(c))).await;
+    }
+    h.send(key(KeyCode::Enter)).await;
+}
+
+#[tokio::test]
+async fn typed_volume_is_set_unless_invalid() {
+    let mut h = Harness::new(backend(), 80, 24).await;
+
+    type_volume(&mut h, "25%").await;
+    assert_eq!(h.state.ui_mode, UIMode::Normal);
+    assert_eq!(h.volume(sink(0)), 25);
+
+    type_volume(&mut h, "nan").await;
+    assert_eq!(h.state.ui_mode, UIMode::InputVolumeValue);
+    assert_eq!(h.volume(sink(0)), 25);
+}
+
+#[tokio::test]
+async fn mute_toggles_selected_entry() {
+    let mut h = Harness::new(backend(), 80, 24).await;
+
+    h.send(Action::RequestMute).await;
+    assert_eq!(
+        h.backend.commands.last(),
+        Some(&Action::MuteEntry(sink(0), true))
+    );
+
+    h.send(Action::RequestMute).await;
+    assert_eq!(
+        h.backend.commands.last(),
+        Some(&Action::MuteEntry(sink(0), false))
+    );
+}
+
+#[tokio::test]
+async fn peaks_update_entries() {
+    let mut h = Harness::new(backend(), 80, 24).await;
+
+    h.backend.peak(sink_input(5), 0.5);
+    h.settle().await;
+
+    let entry = h.state.entries.get(&sink_input(5)).unwrap();
+    assert!((entry.play_entry.as_ref().unwrap().meter.level - 0.5).abs() < f32::EPSILON);
+}
+
+#[tokio::test]
+async fn peaks_between_frames_keep_the_loudest() {
+    let mut h = Harness::new(backend(), 80, 24).await;
+
+    h.backend.peak(sink_input(5), 0.3);
+    h.backend.peak(sink_input(5), 0.7);
+    h.backend.peak(sink_input(5), 0.2);
+    h.settle().await;
+
+    assert!((h.meter(sink_input(5)).level - 0.7).abs() < f32::EPSILON);
+    assert_eq!(
+        h.state.redraw,
+        RedrawType::PeakVolumes(vec![sink_input(5)].into_iter().collect())
+    );
+}
+
+#[tokio::test]
+async fn channel_peaks_update_every_channel() {
+    let mut h = Harness::new(backend(), 80, 24).await;
+
+    h.backend.channel_peaks(sink(0), &[0.1, 0.6]);
+    h.settle().await;
+
+    let play = h.state.entries.get(&sink(0)).unwrap().play_entry.as_ref();
+    let play = play.unwrap();
+    assert_eq!(play.channel_meters.len(), 2);
+    assert!((play.channel_meters[0].level - 0.1).abs() < f32::EPSILON);
+    assert!((play.channel_meters[1].level - 0.6).abs() < f32::EPSILON);
+    assert!((play.meter.level - 0.6).abs() < f32::EPSILON);
+
+    // back to a single meter
+    h.backend.peak(sink(0), 0.3);
+    h.settle().await;
+
+    let play = h.state.entries.get(&sink(0)).unwrap().play_entry.as_ref();
+    assert!(play.unwrap().channel_meters.is_empty());
+}
+
+#[tokio::test]
+async fn clipping_is_held_until_reset() {
+    let mut h = Harness::new(backend(), 80, 24).await;
+
+    h.backend.peak(sink_input(5), 1.0);
+    h.settle().await;
+    h.backend.peak(sink_input(5), 0.2);
+    h.settle().await;
+
+    let meter = h.meter(sink_input(5));
+    assert!(meter.clipped);
+    assert!((meter.level - 0.2).abs() < f32::EPSILON);
+    assert!((meter.hold - 1.0).abs() < f32::EPSILON);
+
+    // volume changes don't touch the meter
+    h.send(Action::MoveDown(1)).await;
+    h.send(Action::RequstChangeVolume(-10)).await;
+    assert!(h.meter(sink_input(5)).clipped);
+
+    h.send(Action::ResetPeaks).await;
+
+    let meter = h.meter(sink_input(5));
+    assert!(!meter.clipped);
+    assert!((meter.hold - 0.2).abs() < f32::EPSILON);
+}
+
+#[tokio::test]
+async fn removed_entry_leaves_page() {
+    let mut h = Harness::new(backend(), 80, 24).await;
+
+    h.send(Action::MoveDown(2)).await;
+    h.send(Action::KillEntry(sink_input(5))).await;
+
+    assert!(h.state.entries.get(&sink_input(5)).is_none());
+    assert_eq!(h.state.page_entries.len(), 2);
+}
+
+#[tokio::test]
+async fn new_default_sink_is_shown() {
+    let mut h = Harness::new(backend(), 80, 24).await;
+
+    h.send(Action::SetDefault(
+        sink(1),
+        "fake_sink.headphones".to_string(),
+    ))
+    .await;
+
+    let headphones = h.state.entries.get(&sink(1)).unwrap();
+    assert!(h.state.is_default(headphones));
+}
+
+#[tokio::test]
+async fn set_as_default_sends_device_name() {
+    let mut h = Harness::new(backend(), 80, 24).await;
+
+    h.send(Action::MoveDown(2)).await;
+    h.send(Action::OpenContextMenu).await;
+    h.send(Action::MoveDown(1)).await;
+    h.send(Action::Confirm).await;
+
+    assert!(h.backend.commands.contains(&Action::SetDefault(
+        sink(1),
+        "fake_sink.headphones".to_string()
+    )));
+    assert_eq!(
+        h.state.default_sink.as_deref(),
+        Some("fake_sink.headphones")
+    );
+    let headphones = h.state.entries.get(&sink(1)).unwrap();
+    assert!(h.state.is_default(headphones));
+    let speakers = h.state.entries.get(&sink(0)).unwrap();
+    assert!(!h.state.is_default(speakers));
+    assert_eq!(h.state.redraw, RedrawType::Entries);
+}
+
+#[tokio::test]
+async fn reconnect_keeps_page_and_selection() {
+    let mut h = Harness::new(backend(), 80, 24).await;
+
+    h.send(Action::ChangePage(PageType::Input)).await;
+    h.send(Action::MoveDown(1)).await;
+    assert_eq!(h.selected(), Some(source(1)));
+
+    // indexes are different after the server restarts
+    let restarted = FakeBackend::default()
+        .sink(0, "Speakers", 50)
+        .source(3, "Webcam", 100)
+        .source(4, "Microphone", 100)
+        .source_output(8, "Recorder", 3, 100);
+    h.reconnect(restarted).await;
+
+    assert_eq!(h.state.current_page, PageType::Input);
+    assert_eq!(h.selected(), Some(source(3)));
+    assert!(h.state.restore.is_none());
+}
+
+#[tokio::test]
+async fn reconnect_keeps_hidden_streams() {
+    let mut h = Harness::new(backend(), 80, 24).await;
+
+    h.send(Action::MoveDown(1)).await;
+    h.send(Action::Hide).await;
+    assert!(!h
+        .state
+        .page_entries
+        .iter_entries()
+        .any(|&e| e == sink_input(5)));
+
+    let restarted = FakeBackend::default()
+        .sink(2, "Speakers", 50)
+        .sink_input(9, "Firefox", 2, 80)
+        .sink(3, "Headphones", 100);
+    h.reconnect(restarted).await;
+
+    let page = h
+        .state
+        .page_entries
+        .iter_entries()
+        .copied()
+        .collect::<Vec<_>>();
+    assert_eq!(page, vec![sink(2), sink(3)]);
+    assert_eq!(h.selected(), Some(sink(3)));
+
+    // later updates of the stream don't show it again
+    h.send(Action::MuteEntry(sink_input(9), true)).await;
+    assert!(h
+        .backend
+        .commands
+        .contains(&Action::MuteEntry(sink_input(9), true)));
+    assert!(!h
+        .state
+        .page_entries
+        .iter_entries()
+        .any(|&e| e == sink_input(9)));
+}
+
+#[tokio::test]
+async fn reconnect_hides_every_stream_with_the_same_name() {
+    let backend = backend()
+        .sink_input(6, "Firefox", 0, 30)
+        .sink_input(8, "Firefox", 0, 60);
+    let mut h = Harness::new(backend, 80, 24).await;
+
+    h.send(Action::MoveDown(1)).await;
+    h.send(Action::Hide).await;
+
+    // one of the three windows didn't come back after the restart
+    let restarted = FakeBackend::default()
+        .sink(2, "Speakers", 50)
+        .sink_input(9, "Firefox", 2, 80)
+        .sink_input(10, "Firefox", 2, 30)
+        .sink(3, "Headphones", 100);
+    h.reconnect(restarted).await;
+
+    let page = h
+        .state
+        .page_entries
+        .iter_entries()
+        .copied()
+        .collect::<Vec<_>>();
+    assert_eq!(page, vec![sink(2), sink(3)]);
+    assert_eq!(
+        h.state.entries.get(&sink(2)).unwrap().hidden,
+        HiddenStatus::HiddenKids
+    );
+    assert!(h.state.restore.is_none());
+}
+
+#[tokio::test]
+async fn context_menu_switches_ports() {
+    let backend = backend().ports(
+        sink(0),
+        &["analog-output-speaker", "analog-output-headphones"],
+    );
+    let mut h = Harness::new(backend, 80, 24).await;
+
+    h.send(Action::OpenContextMenu).await;
+    assert_eq!(h.state.context_options.len(), 4);
+
+    h.send(Action::MoveDown(3)).await;
+    h.send(Action::Confirm).await;
+
+    assert_eq!(
+        h.backend.commands.last(),
+        Some(&Action::SetPort(
+            sink(0),
+            "analog-output-headphones".to_string()
+        ))
+    );
+    let play = h.state.entries.get(&sink(0)).unwrap().play_entry.as_ref();
+    assert_eq!(play.unwrap().active_port, Some(1));
+}
+
+#[tokio::test]
+async fn source_output_can_be_moved_to_another_source() {
+    let mut h = Harness::new(backend(), 80, 24).await;
+
+    h.send(Action::ChangePage(PageType::Input)).await;
+    h.send(Action::MoveDown(2)).await;
+    h.send(Action::OpenContextMenu).await;
+    assert_eq!(h.state.context_options.len(), 2);
+
+    // Move
+    h.send(Action::Confirm).await;
+    assert_eq!(
+        h.state.ui_mode,
+        UIMode::MoveEntry(source_output(7), source(1))
+    );
+
+    h.send(Action::MoveUp(1)).await;
+    h.send(Action::OpenContextMenu).await;
+
+    assert_eq!(
+        h.backend.commands.last(),
+        Some(&Action::MoveEntryToParent(source_output(7), source(0)))
+    );
+    assert_eq!(h.state.ui_mode, UIMode::Normal);
+    let recorder = h.state.entries.get(&source_output(7)).unwrap();
+    assert_eq!(recorder.parent, Some(0));
+    assert_eq!(h.selected(), Some(source_output(7)));
+}
+
+#[tokio::test]
+async fn source_output_can_be_killed() {
+    let mut h = Harness::new(backend(), 80, 24).await;
+
+    h.send(Action::ChangePage(PageType::Input)).await;
+    h.send(Action::MoveDown(2)).await;
+    h.send(Action::OpenContextMenu).await;
+    h.send(Action::MoveDown(1)).await;
+    h.send(Action::Confirm).await;
+
+    assert!(h.state.entries.get(&source_output(7)).is_none());
+    assert_eq!(h.state.page_entries.len(), 2);
+}
+
+fn click(x: u16, y: u16) -> Action {
+    Action::MouseInput(MouseEvent::Down(
+        MouseButton::Left,
+        x,
+        y,
+        KeyModifiers::NONE,
+    ))
+}
+
+#[tokio::test]
+async fn clicking_an_entry_selects_it() {
+    let mut h = Harness::new(backend(), 80, 24).await;
+
+    // Headphones, the third entry, starts in row 9
+    h.send(click(10, 9)).await;
+
+    assert_eq!(h.selected(), Some(sink(1)));
+}
+
+#[tokio::test]
+async fn clicking_and_dragging_volume_bar_sets_volume() {
+    let mut h = Harness::new(backend(), 80, 24).await;
+
+    // the bar of Speakers starts at column 40 and has 35 cells inside the borders
+    h.send(click(54, 3)).await;
+    assert_eq!(h.volume(sink(0)), 60);
+
+    let drag = |x| MouseEvent::Drag(MouseButton::Left, x, 12, KeyModifiers::NONE);
+    h.send(Action::MouseInput(drag(61))).await;
+    assert_eq!(h.volume(sink(0)), 90);
+
+    h.send(Action::MouseInput(MouseEvent::Up(
+        MouseButton::Left,
+        61,
+        12,
+        KeyModifiers::NONE,
+    )))
+    .await;
+    h.send(Action::MouseInput(drag(40))).await;
+    assert_eq!(h.volume(sink(0)), 90);
+}
+
+#[tokio::test]
+async fn scroll_wheel_changes_volume_of_entry_under_pointer() {
+    let mut h = Harness::new(backend(), 80, 24).await;
+
+    h.send(Action::MouseInput(MouseEvent::ScrollUp(
+        10,
+        6,
+        KeyModifiers::NONE,
+    )))
+    .await;
+
+    assert_eq!(h.volume(sink_input(5)), 85);
+    assert_eq!(h.selected(), Some(sink(0)));
+}
+
+#[tokio::test]
+async fn clicking_page_title_changes_page() {
+    let mut h = Harness::new(backend(), 80, 24).await;
+
+    h.send(click(12, 0)).await;
+    assert_eq!(h.state.current_page, PageType::Input);
+
+    h.send(click(20, 0)).await;
+    assert_eq!(h.state.current_page, PageType::Cards);
+}
+
+#[tokio::test]
+async fn clicking_context_menu_option_confirms_it() {
+    let mut h = Harness::new(backend(), 80, 24).await;
+
+    h.send(Action::MouseInput(MouseEvent::Down(
+        MouseButton::Right,
+        10,
+        5,
+        KeyModifiers::NONE,
+    )))
+    .await;
+    assert_eq!(h.selected(), Some(sink_input(5)));
+    assert_eq!(h.state.ui_mode, UIMode::ContextMenu);
+
+    // options start three rows below the top of the menu, Kill is the second one
+    h.send(click(40, 6)).await;
+
+    assert!(h.state.entries.get(&sink_input(5)).is_none());
+    assert_eq!(h.state.ui_mode, UIMode::Normal);
+}
+
+#[tokio::test]
+async fn clicking_outside_context_menu_closes_it() {
+    let mut h = Harness::new(backend(), 80, 24).await;
+
+    h.send(Action::OpenContextMenu).await;
+    h.send(click(1, 20)).await;
+
+    assert_eq!(h.state.ui_mode, UIMode::Normal);
+}
+
+fn key(code: KeyCode) -> Action {
+    Action::KeyPress(KeyEvent::new(code, KeyModifiers::NONE))
+}
+
+async fn search(h: &mut Harness, query: &str) {
+    h.send(key(KeyCode::Char('/'))).await;
+    for c in query.chars() {
+        h.send(key(KeyCode::Char(c))).await;
+    }
+}
+
+fn page(h: &Harness) -> Vec<EntryIdentifier> {
+    h.state.page_entries.iter_entries().copied().collect()
+}
+
+#[tokio::test]
+async fn search_filters_page_until_cleared() {
+    let mut h = Harness::new(backend(), 80, 24).await;
+
+    search(&mut h, "FIRE").await;
+    assert_eq!(h.state.ui_mode, UIMode::Search);
+    // Speakers doesn't match, but stays as the parent of Firefox
+    assert_eq!(page(&h), vec![sink(0), sink_input(5)]);
+    assert_eq!(h.selected(), Some(sink_input(5)));
+
+    h.send(key(KeyCode::Enter)).await;
+    assert_eq!(h.state.ui_mode, UIMode::Normal);
+    assert_eq!(page(&h), vec![sink(0), sink_input(5)]);
+
+    h.send(key(KeyCode::Char('/'))).await;
+    h.send(key(KeyCode::Esc)).await;
+    assert_eq!(h.state.search, "");
+    assert_eq!(page(&h), vec![sink(0), sink_input(5), sink(1)]);
+}
+
+#[tokio::test]
+async fn search_matches_application_properties() {
+    let backend = backend().properties(
+        sink_input(5),
+        &[("media.name", "YouTube - Mozilla Firefox")],
+    );
+    let mut h = Harness::new(backend, 80, 24).await;
+
+    search(&mut h, "youtube").await;
+
+    assert_eq!(page(&h), vec![sink(0), sink_input(5)]);
+}
+
+#[tokio::test]
+async fn next_and_previous_match_skip_other_entries() {
+    let mut h = Harness::new(backend(), 80, 24).await;
+
+    search(&mut h, "o").await;
+    h.send(key(KeyCode::Enter)).await;
+    assert_eq!(page(&h), vec![sink(0), sink_input(5), sink(1)]);
+    assert_eq!(h.selected(), Some(sink_input(5)));
+
+    h.send(Action::NextMatch).await;
+    assert_eq!(h.selected(), Some(sink(1)));
+    h.send(Action::NextMatch).await;
+    assert_eq!(h.selected(), Some(sink_input(5)));
+    h.send(Action::PreviousMatch).await;
+    assert_eq!(h.selected(), Some(sink(1)));
+}
+
+fn rule(name: &str, toml: &str) -> Result<Rule, RSError> {
+    Rule::new(name, &toml::from_str::<RuleConfig>(toml).unwrap())
+}
+
+#[tokio::test]
+async fn rules_set_volume_mute_and_target_of_matching_streams() {
+    let backend = backend().properties(
+        sink_input(5),
+        &[
+            ("application.name", "Firefox"),
+            ("application.process.binary", "firefox"),
+        ],
+    );
+    let mut h = Harness::new(backend, 80, 24).await;
+
+    let rules = vec![
+        rule("browser", "binary = 'FIREFOX'\nvolume = '70%'").unwrap(),
+        rule(
+            "firefox",
+            "application = 'Firefox'\nmute = true\ntarget = 'Headphones'",
+        )
+        .unwrap(),
+        rule("calls", "role = 'phone'\nvolume = '10'").unwrap(),
+    ];
+    rules::apply(&rules, &[], sink_input(5), &h.state).await;
+    h.settle().await;
+
+    let firefox = h.state.entries.get(&sink_input(5)).unwrap();
+    assert_eq!(h.volume(sink_input(5)), 70);
+    assert!(firefox.play_entry.as_ref().unwrap().mute);
+    assert_eq!(firefox.parent, Some(1));
+}
+
+#[tokio::test]
+async fn rules_apply_to_streams_started_after_connecting() {
+    let ruled = [("application.process.binary", "ruled")];
+    // already playing when rsmixer connected, it keeps the volume it had
+    let mut h = Harness::new(backend().properties(sink_input(5), &ruled), 80, 24).await;
+    assert_eq!(h.volume(sink_input(5)), 80);
+    assert!(!h.muted(sink_input(5)));
+
+    h.backend.add(
+        FakeBackend::default()
+            .sink_input(6, "Game", 0, 100)
+            .properties(sink_input(6), &ruled),
+    );
+    h.settle().await;
+    assert_eq!(h.volume(sink_input(6)), 40);
+    assert!(h.muted(sink_input(6)));
+
+    // nor are streams that come back after reconnecting new
+    let restarted = backend()
+        .properties(sink_input(5), &ruled)
+        .sink_input(6, "Game", 0, 100)
+        .properties(sink_input(6), &ruled);
+    h.reconnect(restarted).await;
+    assert_eq!(h.volume(sink_input(5)), 80);
+    assert_eq!(h.volume(sink_input(6)), 100);
+    assert!(!h.muted(sink_input(6)));
+}
+
+#[tokio::test]
+async fn rules_need_a_property_to_match_and_a_valid_volume() {
+    assert!(matches!(
+        rule("everything", "volume = '50'"),
+        Err(RSError::InvalidRule(_, _))
+    ));
+    assert!(matches!(
+        rule("loud", "binary = 'mpv'\nvolume = 'loud'"),
+        Err(RSError::InvalidRule(_, _))
+    ));
+}
+
+fn card(index: u32) -> EntryIdentifier {
+    EntryIdentifier::new(EntryType::Card, index)
+}
+
+// changes everything a scene remembers
+async fn mess_up(h: &mut Harness) {
+    h.send(Action::RequstChangeVolume(-20)).await;
+    h.send(Action::MuteEntry(sink_input(5), true)).await;
+    h.send(Action::MoveEntryToParent(sink_input(5), sink(1)))
+        .await;
+    h.send(Action::SetDefault(
+        sink(1),
+        "fake_sink.headphones".to_string(),
+    ))
+    .await;
+    h.send(Action::ChangeCardProfile(card(0), "off".to_string()))
+        .await;
+}
+
+#[tokio::test]
+async fn scene_restores_everything_it_captured() {
+    let mut h = Harness::new(backend(), 80, 24).await;
+    let scene = Scene::capture(&h.state);
+    assert!(scene.restore(&h.state).is_empty());
+
+    mess_up(&mut h).await;
+    assert_eq!(h.volume(sink(0)), 30);
+
+    for action in scene.restore(&h.state) {
+        h.send(action).await;
+    }
+
+    assert_eq!(h.volume(sink(0)), 50);
+    assert_eq!(Scene::capture(&h.state), scene);
+    assert!(scene.restore(&h.state).is_empty());
+}
+
+#[tokio::test]
+async fn scenes_page_restores_selected_scene() {
+    let mut h = Harness::new(backend(), 80, 24).await;
+
+    let mut saved = linked_hash_map::LinkedHashMap::new();
+    saved.insert("empty".to_string(), Scene::default());
+    saved.insert("current".to_string(), Scene::capture(&h.state));
+    scenes::store(&saved).unwrap();
+
+    mess_up(&mut h).await;
+    h.send(Action::ChangePage(PageType::Scenes)).await;
+    assert!(h.state.page_entries.iter_entries().next().is_none());
+    assert_eq!(h.state.scenes, saved);
+
+    h.send(Action::MoveDown(1)).await;
+    assert_eq!(h.state.selected_scene, 1);
+    h.send(Action::OpenContextMenu).await;
+
+    // and the second pass was done after the sync
+    assert!(h.backend.commands.contains(&Action::RequestSync));
+    assert!(h.state.restoring_scene.is_none());
+    assert_eq!(h.volume(sink(0)), 50);
+    assert_eq!(h.state.default_sink, saved["current"].default_sink);
+    let firefox = h.state.entries.get(&sink_input(5)).unwrap();
+    assert_eq!(firefox.parent, Some(0));
+
+    scenes::remove();
+}
+
+fn profile(h: &Harness, ident: EntryIdentifier) -> String {
+    let card = h.state.entries.get(&ident).unwrap().card_entry.as_ref();
+    let card = card.unwrap();
+    card.profiles[card.selected_profile.unwrap()].name.clone()
+}
+
+fn cards() -> FakeBackend {
+    FakeBackend::default().card(
+        0,
+        "Built-in Audio",
+        &[
+            "output:analog-stereo",
+            "output:hdmi-stereo",
+            "output:analog-surround-51",
+            "off",
+        ],
+        2,
+    )
+}
+
+#[tokio::test]
+async fn card_menu_selects_active_profile_among_shown_ones() {
+    let backend = cards().unavailable_profile(0, "output:hdmi-stereo");
+    let mut h = Harness::new(backend, 80, 24).await;
+
+    h.send(Action::ChangePage(PageType::Cards)).await;
+    h.send(Action::OpenContextMenu).await;
+
+    assert_eq!(h.state.context_options.len(), 3);
+    assert_eq!(h.state.selected_context, 1);
+
+    // the last one can still be reached and chosen
+    h.send(Action::MoveDown(5)).await;
+    h.send(Action::Confirm).await;
+    assert_eq!(profile(&h, card(0)), "off");
+}
+
+#[tokio::test]
+async fn unavailable_profiles_cant_be_chosen() {
+    // the active profile is shown even when it can't be used
+    let backend = cards()
+        .unavailable_profile(0, "output:hdmi-stereo")
+        .unavailable_profile(0, "output:analog-surround-51");
+    let mut h = Harness::new(backend, 80, 24).await;
+
+    h.send(Action::ChangePage(PageType::Cards)).await;
+    h.send(Action::OpenContextMenu).await;
+    assert_eq!(h.state.context_options.len(), 3);
+    assert_eq!(h.state.selected_context, 0);
+
+    // moving skips it both ways
+    h.send(Action::MoveDown(1)).await;
+    assert_eq!(h.state.selected_context, 2);
+    h.send(Action::MoveUp(1)).await;
+    assert_eq!(h.state.selected_context, 0);
+
+    // and so does clicking it
+    h.send(click(40, 6)).await;
+    assert_eq!(h.state.ui_mode, UIMode::ContextMenu);
+    assert_eq!(h.state.selected_context, 0);
+    assert_eq!(profile(&h, card(0)), "output:analog-surround-51");
+
+    h.send(Action::MoveDown(1)).await;
+    h.send(Action::Confirm).await;
+    assert_eq!(profile(&h, card(0)), "off");
+}
+
+#[tokio::test]
+async fn undo_and_redo_revert_changes_made_from_the_ui() {
+    let mut h = Harness::new(backend(), 80, 24).await;
+
+    // held key, undone at once
+    h.send(Action::RequstChangeVolume(-10)).await;
+    h.send(Action::RequstChangeVolume(-10)).await;
+    h.send(Action::RequestMute).await;
+    h.send(Action::ChangePage(PageType::Cards)).await;
+    h.send(Action::OpenContextMenu).await;
+    h.send(Action::MoveDown(1)).await;
+    h.send(Action::Confirm).await;
+    assert_eq!(profile(&h, card(0)), "off");
+
+    h.send(Action::Undo).await;
+    assert_eq!(profile(&h, card(0)), "output:analog-stereo");
+    h.send(Action::Undo).await;
+    assert!(!h.muted(sink(0)));
+    h.send(Action::Undo).await;
+    assert_eq!(h.volume(sink(0)), 50);
+
+    h.send(Action::Redo).await;
+    assert_eq!(h.volume(sink(0)), 30);
+    h.send(Action::Redo).await;
+    assert!(h.muted(sink(0)));
+}
+
+#[tokio::test]
+async fn undo_skips_changes_overridden_by_other_clients() {
+    let mut h = Harness::new(backend(), 80, 24).await;
+
+    h.send(Action::RequestMute).await;
+    h.send(Action::RequstChangeVolume(-20)).await;
+
+    // another client, the history doesn't know about it
+    let entry = h.state.entries.get(&sink(0)).unwrap();
+    let mut vols = entry.play_entry.as_ref().unwrap().volume;
+    vols.scale(percent_to_volume(80));
+    h.send(Action::SetVolume(sink(0), vols)).await;
+
+    h.send(Action::Undo).await;
+    assert_eq!(h.volume(sink(0)), 80);
+    assert!(!h.muted(sink(0)));
+}
+
+fn call() -> FakeBackend {
+    backend()
+        .sink_input(9, "Zoom", 1, 100)
+        .properties(sink_input(9), &[("media.role", "phone")])
+}
+
+// lowered streams are raised back with a fade
+async fn wait_for_ramp(h: &mut Harness) {
+    h.advance(Duration::from_secs(1)).await;
+}
+
+// ducking is off unless the config turns it on
+async fn ducking(backend: FakeBackend) -> Harness {
+    let mut h = Harness::new(backend, 80, 24).await;
+    assert!(!h.state.ducking.is_enabled());
+    h.send(Action::ToggleDucking).await;
+    h
+}
+
+#[tokio::test]
+async fn other_streams_are_lowered_while_a_call_plays() {
+    let mut h = ducking(call()).await;
+
+    assert!(h.state.ducking.active);
+    assert_eq!(h.volume(sink_input(5)), 40);
+    assert_eq!(h.volume(sink_input(9)), 100);
+
+    h.send(Action::KillEntry(sink_input(9))).await;
+    assert!(!h.state.ducking.active);
+    wait_for_ramp(&mut h).await;
+    assert_eq!(h.volume(sink_input(5)), 80);
+}
+
+#[tokio::test]
+async fn clicking_ducking_toggle_turns_it_off() {
+    let mut h = ducking(call()).await;
+
+    h.send(click(70, 0)).await;
+    assert_eq!(h.state.ducking.enabled, Some(false));
+    assert!(!h.state.ducking.active);
+    wait_for_ramp(&mut h).await;
+    assert_eq!(h.volume(sink_input(5)), 80);
+
+    h.send(Action::ToggleDucking).await;
+    assert_eq!(h.volume(sink_input(5)), 40);
+}
+
+#[tokio::test]
+async fn paused_calls_dont_lower_other_streams() {
+    let mut h = ducking(call().corked(sink_input(9))).await;
+    assert!(!h.state.ducking.active);
+    assert_eq!(h.volume(sink_input(5)), 80);
+
+    // the call starts playing
+    h.backend.add(call());
+    h.settle().await;
+    assert!(h.state.ducking.active);
+    assert_eq!(h.volume(sink_input(5)), 40);
+}
+
+#[tokio::test]
+async fn streams_lowered_before_reconnecting_arent_lowered_again() {
+    let mut h = ducking(call()).await;
+    assert_eq!(h.volume(sink_input(5)), 40);
+
+    // the server comes back with the stream still lowered
+    let restarted = FakeBackend::default()
+        .sink(2, "Speakers", 50)
+        .sink_input(6, "Firefox", 2, 40)
+        .sink_input(7, "Zoom", 2, 100)
+        .properties(sink_input(7), &[("media.role", "phone")]);
+    h.reconnect(restarted).await;
+    assert!(h.state.ducking.active);
+    assert_eq!(h.volume(sink_input(6)), 40);
+
+    h.send(Action::KillEntry(sink_input(7))).await;
+    wait_for_ramp(&mut h).await;
+    assert_eq!(h.volume(sink_input(6)), 80);
+}
+
+#[tokio::test]
+async fn lowered_streams_are_raised_before_exiting() {
+    let mut h = ducking(call()).await;
+    assert_eq!(h.volume(sink_input(5)), 40);
+
+    h.send(key(KeyCode::Char('q'))).await;
+    match h.backend.commands.last() {
+        Some(Action::SetVolume(ident, vols)) => {
+            assert_eq!(*ident, sink_input(5));
+            assert_eq!(volume_to_percent(vols.max()), 80);
+        }
+        last => panic!("volume wasn't raised, last command: {:?}", last),
+    }
+}
+
+#[tokio::test]
+async fn fade_to_moves_volume_over_time_until_changed() {
+    let mut h = Harness::new(backend(), 80, 24).await;
+
+    h.send(Action::MoveDown(1)).await;
+    let fade = Action::try_from("fade_to(20,400)".to_string()).unwrap();
+    h.send(fade).await;
+    assert_eq!(h.volume(sink_input(5)), 80);
+
+    h.advance(Duration::from_millis(200)).await;
+    assert_eq!(h.volume(sink_input(5)), 50);
+    h.advance(Duration::from_secs(1)).await;
+    assert_eq!(h.volume(sink_input(5)), 20);
+
+    h.send(Action::Undo).await;
+    assert_eq!(h.volume(sink_input(5)), 80);
+
+    // changing volume stops a fade that's still going
+    h.send(Action::RequestFade(None, 0, 400, FadeCurve::Log))
+        .await;
+    h.send(Action::RequstChangeVolume(10)).await;
+    h.advance(Duration::from_secs(1)).await;
+    assert_eq!(h.volume(sink_input(5)), 90);
+}
+
+#[tokio::test]
+async fn fade_to_can_name_an_entry() {
+    let mut h = Harness::new(backend(), 80, 24).await;
+
+    let fade = Action::try_from("fade_to(Headphones, 20, 400, log)".to_string()).unwrap();
+    h.send(fade).await;
+    let fade = Action::try_from("fade_to(sink-input:5,0,400)".to_string()).unwrap();
+    h.send(fade).await;
+    // nothing to fade
+    let fade = Action::try_from("fade_to(Trumpet,0,400)".to_string()).unwrap();
+    h.send(fade).await;
+
+    h.advance(Duration::from_secs(1)).await;
+    assert_eq!(h.volume(sink(1)), 20);
+    assert_eq!(h.volume(sink_input(5)), 0);
+    assert_eq!(h.volume(sink(0)), 50);
+    assert_eq!(h.selected(), Some(sink(0)));
+}
+
+#[tokio::test]
+async fn fade_to_arguments_are_told_apart() {
+    let parse = |s: &str| Action::try_from(s.to_string()).ok();
+    let fade = |target: Option<&str>, level, ms, curve| {
+        Some(Action::RequestFade(
+            target.map(|t| t.to_string()),
+            level,
+            ms,
+            curve,
+        ))
+    };
+
+    assert_eq!(
+        parse("fade_to(30,2000,log)"),
+        fade(None, 30, 2000, FadeCurve::Log)
+    );
+    assert_eq!(
+        parse("fade_to(Firefox,30,2000)"),
+        fade(Some("Firefox"), 30, 2000, FadeCurve::Linear)
+    );
+    assert_eq!(
+        parse("fade_to(5,30,2000,linear)"),
+        fade(Some("5"), 30, 2000, FadeCurve::Linear)
+    );
+    assert_eq!(parse("fade_to(Firefox,30)"), None);
+    assert_eq!(parse("fade_to(Firefox,30,2000,log,1)"), None);
+
+    // written back so that it's read the same way
+    for action in &[
+        fade(None, 30, 2000, FadeCurve::Log),
+        fade(Some("5"), 30, 2000, FadeCurve::Linear),
+    ] {
+        let action = action.clone().unwrap();
+        assert_eq!(parse(&action.to_string()), Some(action));
+    }
+}
+
+fn limit(name: &str, toml: &str) -> Result<Limit, RSError> {
+    Limit::new(name, &toml::from_str::<LimitConfig>(toml).unwrap())
+}
+
+#[tokio::test]
+async fn limits_lower_matching_devices_and_streams() {
+    let backend = backend().properties(sink_input(5), &[("application.process.binary", "firefox")]);
+    let mut h = Harness::new(backend, 80, 24).await;
+
+    let limits = vec![
+        limit(
+            "headphones",
+            "device = 'fake_sink.headphones'\nmax_volume = '60%'",
+        )
+        .unwrap(),
+        limit("browser", "binary = 'Firefox'\nmax_volume = '50'").unwrap(),
+    ];
+    for ident in &[sink(0), sink(1), sink_input(5)] {
+        limits::enforce(&limits, *ident, &h.state).await;
+    }
+    h.settle().await;
+
+    assert_eq!(h.volume(sink(0)), 50);
+    assert_eq!(h.volume(sink(1)), 60);
+    assert_eq!(h.volume(sink_input(5)), 50);
+
+    // and rules can't set more than that either
+    let rules = vec![rule("loud", "binary = 'firefox'\nvolume = '100%'").unwrap()];
+    rules::apply(&rules, &limits, sink_input(5), &h.state).await;
+    h.settle().await;
+    assert_eq!(h.volume(sink_input(5)), 50);
+}
+
+fn limited() -> FakeBackend {
+    FakeBackend::default()
+        .sink(0, "Speakers", 100)
+        .sink_input(5, "Game", 0, 40)
+        .properties(sink_input(5), &[("application.process.binary", "limited")])
+}
+
+#[tokio::test]
+async fn limits_from_the_config_cap_volume_set_from_the_ui() {
+    let mut h = Harness::new(limited(), 80, 24).await;
+    h.send(Action::MoveDown(1)).await;
+
+    h.send(Action::RequstChangeVolume(30)).await;
+    assert_eq!(h.volume(sink_input(5)), 50);
+
+    h.send(Action::RequstChangeVolume(-20)).await;
+    type_volume(&mut h, "90").await;
+    assert_eq!(h.volume(sink_input(5)), 50);
+
+    // the sink has no limit
+    h.send(Action::MoveUp(1)).await;
+    type_volume(&mut h, "120").await;
+    assert_eq!(h.volume(sink(0)), 120);
+}
+
+#[tokio::test]
+async fn limits_from_the_config_lower_volume_raised_by_other_clients() {
+    let mut h = Harness::new(limited(), 80, 24).await;
+
+    let entry = h.state.entries.get(&sink_input(5)).unwrap();
+    let mut vols = entry.play_entry.as_ref().unwrap().volume;
+    vols.scale(percent_to_volume(90));
+    h.send(Action::SetVolume(sink_input(5), vols)).await;
+
+    assert_eq!(h.volume(sink_input(5)), 50);
+}
+
+#[tokio::test]
+async fn limits_need_something_to_match_and_a_valid_max_volume() {
+    assert!(matches!(
+        limit("everything", "max_volume = '50'"),
+        Err(RSError::InvalidLimit(_, _))
+    ));
+    assert!(matches!(
+        limit("speakers", "device = 'speakers'"),
+        Err(RSError::InvalidLimit(_, _))
+    ));
+    assert!(matches!(
+        limit("speakers", "device = 'speakers'\nmax_volume = '200%'"),
+        Err(RSError::InvalidLimit(_, _))
+    ));
+    // devices don't have application properties
+    assert!(matches!(
+        limit(
+            "speakers",
+            "device = 'speakers'\nbinary = 'firefox'\nmax_volume = '50'"
+        ),
+        Err(RSError::InvalidLimit(_, _))
+    ));
+}
+
+#[tokio::test]
+async fn socket_runs_actions_like_key_bindings() {
+    let mut h = Harness::new(backend(), 80, 24).await;
+
+    assert_eq!(h.request("down(2)").await, "ok");
+    assert_eq!(h.selected(), Some(sink(1)));
+
+    assert_eq!(h.request(" lower_volume(30)\n").await, "ok");
+    assert_eq!(h.volume(sink(1)), 70);
+}
+
+#[tokio::test]
+async fn socket_actions_can_target_an_entry() {
+    let mut h = Harness::new(backend(), 80, 24).await;
+
+    assert_eq!(h.request("raise_volume(10) Firefox").await, "ok");
+    assert_eq!(h.request("mute @DEFAULT_SINK@").await, "ok");
+    assert_eq!(h.request("lower_volume(20) sink:1").await, "ok");
+
+    assert_eq!(h.volume(sink_input(5)), 90);
+    assert!(h.muted(sink(0)));
+    assert_eq!(h.volume(sink(1)), 80);
+    // the UI stays where it was
+    assert_eq!(h.selected(), Some(sink(0)));
+    assert_eq!(h.volume(sink(0)), 50);
+}
+
+#[tokio::test]
+async fn socket_answers_with_state() {
+    let mut h = Harness::new(backend(), 80, 24).await;
+    h.send(Action::MoveDown(1)).await;
+
+    let reply = h.request("state").await;
+    let state: serde_json::Value = serde_json::from_str(&reply).unwrap();
+
+    assert_eq!(state["page"], "Output");
+    assert_eq!(
+        state["selected"],
+        serde_json::json!({ "type": "sink-input", "index": 5 })
+    );
+    assert_eq!(state["entries"].as_array().unwrap().len(), 7);
+}
+
+#[tokio::test]
+async fn socket_reports_invalid_requests() {
+    let mut h = Harness::new(backend(), 80, 24).await;
+
+    assert_eq!(
+        h.request("explode").await,
+        "error: 'explode' is not a valid action"
+    );
+    assert_eq!(
+        h.request("mute Trumpet").await,
+        "error: 'Trumpet' doesn't match any entry"
+    );
+    assert_eq!(
+        h.request("down(1) Firefox").await,
+        "error: 'down(1)' can't be used on a chosen entry"
+    );
+    assert_eq!(h.selected(), Some(sink(0)));
+    assert!(!h.muted(sink(0)));
+}
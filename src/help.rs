@@ -1,6 +1,6 @@
 use crate::{config::keys, models::PageType, Action, RSError, BINDINGS};
 
-use std::{collections::HashSet, mem::discriminant};
+use std::{collections::BTreeSet, mem::discriminant};
 
 #[derive(Debug)]
 pub struct HelpLine {
@@ -25,7 +25,7 @@ impl ActionMatcher {
 pub fn generate() -> Vec<HelpLine> {
     let mut categories = Vec::new();
 
-    let mut volume_deltas = HashSet::new();
+    let mut volume_deltas = BTreeSet::new();
 
     for (_, v) in (*BINDINGS).get().iter() {
         if let Action::RequstChangeVolume(x) = v {
@@ -54,6 +54,36 @@ pub fn generate() -> Vec<HelpLine> {
         "Mute/unmute".to_string(),
         vec![ActionMatcher::Concrete(Action::RequestMute)],
     ));
+    categories.push((
+        "Show channels".to_string(),
+        vec![ActionMatcher::Concrete(Action::ToggleChannels)],
+    ));
+    categories.push((
+        "Reset peaks".to_string(),
+        vec![ActionMatcher::Concrete(Action::ResetPeaks)],
+    ));
+    categories.push((
+        "Search".to_string(),
+        vec![ActionMatcher::Concrete(Action::StartSearch)],
+    ));
+    categories.push((
+        "Next/previous match".to_string(),
+        vec![
+            ActionMatcher::Concrete(Action::NextMatch),
+            ActionMatcher::Concrete(Action::PreviousMatch),
+        ],
+    ));
+    categories.push((
+        "Undo/redo".to_string(),
+        vec![
+            ActionMatcher::Concrete(Action::Undo),
+            ActionMatcher::Concrete(Action::Redo),
+        ],
+    ));
+    categories.push((
+        "Ducking on/off".to_string(),
+        vec![ActionMatcher::Concrete(Action::ToggleDucking)],
+    ));
     categories.push((
         "Change page".to_string(),
         vec![ActionMatcher::Any(Action::ChangePage(PageType::Output))],
@@ -85,7 +115,12 @@ pub fn generate() -> Vec<HelpLine> {
                 }
             }
         }
-        help_lines.push(hl);
+        // bindings are kept in a hash map, so sort them to keep the order between runs
+        hl.key_events.sort();
+        // actions without any bindings (e.g. in older configs) are left out
+        if !hl.key_events.is_empty() {
+            help_lines.push(hl);
+        }
     }
 
     help_lines
@@ -18,10 +18,12 @@ pub async fn start(mut rx: Receiver<Action>) {
                     crossterm::event::Event::Key(event) => {
                         DISPATCH.event(Action::KeyPress(event)).await;
                     }
+                    crossterm::event::Event::Mouse(event) => {
+                        DISPATCH.event(Action::MouseInput(event)).await;
+                    }
                     crossterm::event::Event::Resize(_, _) => {
                         DISPATCH.event(Action::Redraw).await;
                     }
-                    _ => {}
                 };
             }
             ev = recv_event => {
@@ -1,6 +1,7 @@
 extern crate crossbeam_channel as cb_channel;
 extern crate libpulse_binding as pulse;
 
+mod cli;
 mod config;
 mod entry;
 mod errors;
@@ -10,6 +11,7 @@ mod input;
 mod models;
 mod pa;
 mod run;
+mod socket;
 mod ui;
 
 pub use errors::RSError;
@@ -17,6 +19,7 @@ pub use models::Action;
 
 use config::{RsMixerConfig, Variables};
 use ev_apple::{Dispatch, Senders};
+use models::PeakTable;
 
 use tokio::runtime;
 
@@ -39,6 +42,7 @@ lazy_static! {
     pub static ref STYLES: Storage<Styles> = Storage::new();
     pub static ref VARIABLES: Storage<Variables> = Storage::new();
     pub static ref BINDINGS: Storage<MultiMap<KeyEvent, Action>> = Storage::new();
+    pub static ref PEAKS: PeakTable = PeakTable::default();
 }
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -55,6 +59,9 @@ struct CliOptions {
 
     #[options(help = "show this text")]
     help: bool,
+
+    #[options(command)]
+    command: Option<cli::Command>,
 }
 
 async fn launch() -> Result<(), RSError> {
@@ -77,12 +84,16 @@ async fn launch() -> Result<(), RSError> {
     let mut config = RsMixerConfig::load()?;
 
     let (styles, bindings, variables) = config.interpret()?;
+    config.save()?;
 
     STYLES.set(styles);
     BINDINGS.set(bindings);
     VARIABLES.set(variables);
 
-    run::run().await
+    match opts.command {
+        Some(command) => cli::run(command).await,
+        None => run::run().await,
+    }
 }
 
 fn main() -> Result<(), RSError> {
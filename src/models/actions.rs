@@ -1,6 +1,6 @@
 use crate::{
     entry::{Entry, EntryIdentifier},
-    models::PageType,
+    models::{Fade, FadeCurve, MonitorTarget, PageType},
 };
 
 use ev_apple::{messages, Message};
@@ -9,7 +9,7 @@ use std::collections::HashMap;
 
 use pulse::volume::ChannelVolumes;
 
-use crossterm::event::KeyEvent;
+use crossterm::event::{KeyEvent, MouseEvent};
 
 use statics::*;
 
@@ -22,7 +22,8 @@ messages!(Action,
     // entry updates
     EntryRemoved(EntryIdentifier) => MAIN_MESSAGE,
     EntryUpdate(EntryIdentifier, Box<Entry>) => MAIN_MESSAGE,
-    PeakVolumeUpdate(EntryIdentifier, f32) => MAIN_MESSAGE,
+    // names of the default sink and source reported by the server
+    DefaultsUpdate(Option<String>, Option<String>) => MAIN_MESSAGE,
 
     // move around the UI
     MoveUp(u16) => MAIN_MESSAGE,
@@ -37,6 +38,9 @@ messages!(Action,
     // request volume change where the argument is a
     // number of percentage points it should be changed by
     RequstChangeVolume(i16) => MAIN_MESSAGE,
+    // move volume of the given entry (the selected one when there's none)
+    // to a percentage over that many milliseconds
+    RequestFade(Option<String>, u16, u64, FadeCurve) => MAIN_MESSAGE,
 
     // context menus
     OpenContextMenu => MAIN_MESSAGE,
@@ -46,21 +50,58 @@ messages!(Action,
     ShowHelp => MAIN_MESSAGE,
 
     Hide => MAIN_MESSAGE,
+    // show/hide volumes of every channel of the selected entry
+    ToggleChannels => MAIN_MESSAGE,
+    // clear peak-hold markers and clip indicators
+    ResetPeaks => MAIN_MESSAGE,
+
+    // type a query, only matching entries are shown until it's cleared
+    StartSearch => MAIN_MESSAGE,
+    // the query was edited, the page has to be filtered again
+    SearchChanged => MAIN_MESSAGE,
+    // select the next/previous entry matching the query
+    NextMatch => MAIN_MESSAGE,
+    PreviousMatch => MAIN_MESSAGE,
+
+    // revert the last change made from the UI, or make it again
+    Undo => MAIN_MESSAGE,
+    Redo => MAIN_MESSAGE,
+
+    // turn lowering other streams during calls on or off
+    ToggleDucking => MAIN_MESSAGE,
 
     // PulseAudio connection status
     RetryIn(u64) => MAIN_MESSAGE,
     ConnectToPA => MAIN_MESSAGE,
     PADisconnected => MAIN_MESSAGE,
+    // every entry that existed when connecting was already sent
+    InitialStateLoaded => MAIN_MESSAGE,
+    // PulseAudio handled all commands sent before RequestSync
+    Synced => MAIN_MESSAGE,
 
     KeyPress(KeyEvent) => MAIN_MESSAGE,
+    MouseInput(MouseEvent) => MAIN_MESSAGE,
+
+    // control socket, every request gets exactly one reply with the same id
+    SocketRequest(u64, String) => MAIN_MESSAGE,
+    SocketReply(u64, String) => SOCKET_MESSAGE,
 
     MuteEntry(EntryIdentifier, bool) => PA_MESSAGE,
     MoveEntryToParent(EntryIdentifier, EntryIdentifier) => PA_MESSAGE,
     ChangeCardProfile(EntryIdentifier, String) => PA_MESSAGE,
     SetVolume(EntryIdentifier, ChannelVolumes) => PA_MESSAGE,
-    CreateMonitors(HashMap<EntryIdentifier, Option<u32>>) => PA_MESSAGE,
+    // handled by the PulseAudio loop, which sends SetVolume every tick until it's done
+    Fade(Fade) => PA_MESSAGE,
+    CreateMonitors(HashMap<EntryIdentifier, MonitorTarget>) => PA_MESSAGE,
     SetSuspend(EntryIdentifier, bool) => PA_MESSAGE,
+    // name of the sink/source port that should become active
+    SetPort(EntryIdentifier, String) => PA_MESSAGE,
+    // name of the sink/source that should become the default
+    SetDefault(EntryIdentifier, String) => PA_MESSAGE,
     KillEntry(EntryIdentifier) => PA_MESSAGE,
+    RequestSync => PA_MESSAGE,
+    // send every entry again, followed by InitialStateLoaded
+    RequestState => PA_MESSAGE,
     PADisconnected2 => PA_MESSAGE,
 );
 
@@ -71,6 +112,7 @@ pub mod statics {
     pub static PA_MESSAGE: u32 = 2;
     pub static RUN_PA_MESSAGE: u32 = 3;
     pub static INPUT_MESSAGE: u32 = 4;
+    pub static SOCKET_MESSAGE: u32 = 5;
 
     pub static EXIT_MESSAGE_ID: u32 = 0;
 }
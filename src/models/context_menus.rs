@@ -1,52 +1,120 @@
+use super::RSState;
+
 use crate::{
     entry::{Entry, EntryIdentifier, EntryType},
-    Action, DISPATCH,
+    Action, DISPATCH, VARIABLES,
 };
 
 pub fn context_menu(entry: &Entry) -> Vec<ContextMenuOption> {
     match entry.entry_type {
-        EntryType::Source | EntryType::Sink => vec![
-            if entry.play_entry.as_ref().unwrap().suspended {
+        EntryType::Source | EntryType::Sink => {
+            let play = entry.play_entry.as_ref().unwrap();
+
+            let mut options = vec![if play.suspended {
                 ContextMenuOption::Resume
             } else {
                 ContextMenuOption::Suspend
-            },
-            ContextMenuOption::SetAsDefault,
-        ],
-        EntryType::SinkInput => vec![ContextMenuOption::Move, ContextMenuOption::Kill],
-        EntryType::SourceOutput => vec![],
-        EntryType::Card => entry
-            .card_entry
-            .as_ref()
-            .unwrap()
-            .profiles
-            .iter()
-            .map(|p| ContextMenuOption::ChangeCardProfile(p.name.clone(), p.description.clone()))
-            .collect(),
+            }];
+
+            // PulseAudio picks the default device by its name
+            if let Some(device_name) = &play.device_name {
+                options.push(ContextMenuOption::SetAsDefault(device_name.clone()));
+            }
+
+            // there's nothing to switch to with a single port
+            if play.ports.len() > 1 {
+                options.extend(play.ports.iter().map(|p| {
+                    let description = if p.available {
+                        p.description.clone()
+                    } else {
+                        format!("{} (unplugged)", p.description)
+                    };
+                    ContextMenuOption::SetPort(p.name.clone(), description)
+                }));
+            }
+
+            options
+        }
+        EntryType::SinkInput | EntryType::SourceOutput => {
+            vec![ContextMenuOption::Move, ContextMenuOption::Kill]
+        }
+        EntryType::Card => {
+            let card = entry.card_entry.as_ref().unwrap();
+            let hide_unavailable = VARIABLES.get().hide_unavailable_profiles;
+
+            card.profiles
+                .iter()
+                .enumerate()
+                .filter(|(i, p)| {
+                    p.available || !hide_unavailable || card.selected_profile == Some(*i)
+                })
+                .map(|(_, p)| {
+                    let description = format!(
+                        "{} ({} out, {} in, priority {})",
+                        p.description, p.sinks, p.sources, p.priority
+                    );
+                    ContextMenuOption::ChangeCardProfile(p.name.clone(), description, p.available)
+                })
+                .collect()
+        }
     }
 }
 
+// the option selected when the menu opens: the active profile of a card
+// (unless it can't be used anymore), otherwise the first one that can be chosen
+pub fn initial_option(entry: &Entry, options: &[ContextMenuOption]) -> usize {
+    let active = entry.card_entry.as_ref().and_then(|card| {
+        card.selected_profile
+            .map(|i| card.profiles[i].name.as_str())
+    });
+
+    options
+        .iter()
+        .position(|o| match o {
+            ContextMenuOption::ChangeCardProfile(name, _, available) => {
+                *available && Some(name.as_str()) == active
+            }
+            _ => false,
+        })
+        .or_else(|| options.iter().position(|o| o.is_available()))
+        .unwrap_or(0)
+}
+
 #[derive(PartialEq, Clone)]
 pub enum ContextMenuOption {
     MoveToEntry(EntryIdentifier, String),
-    ChangeCardProfile(String, String),
+    // name, description, is available
+    ChangeCardProfile(String, String, bool),
+    SetPort(String, String),
     Kill,
     Move,
     Suspend,
     Resume,
-    SetAsDefault,
+    // name of the sink/source
+    SetAsDefault(String),
+}
+
+impl ContextMenuOption {
+    // unavailable options are still shown, but greyed out
+    pub fn is_available(&self) -> bool {
+        match self {
+            ContextMenuOption::ChangeCardProfile(_, _, available) => *available,
+            _ => true,
+        }
+    }
 }
 
 impl From<ContextMenuOption> for String {
     fn from(option: ContextMenuOption) -> Self {
         match option {
             ContextMenuOption::MoveToEntry(_, s) => s,
-            ContextMenuOption::ChangeCardProfile(_, s) => s,
+            ContextMenuOption::ChangeCardProfile(_, s, _) => s,
+            ContextMenuOption::SetPort(_, s) => s,
             ContextMenuOption::Kill => "Kill".into(),
             ContextMenuOption::Move => "Move".into(),
             ContextMenuOption::Suspend => "Suspend".into(),
             ContextMenuOption::Resume => "Resume".into(),
-            ContextMenuOption::SetAsDefault => "Set as default".into(),
+            ContextMenuOption::SetAsDefault(_) => "Set as default".into(),
         }
     }
 }
@@ -56,31 +124,44 @@ pub enum ContextMenuEffect {
     MoveEntry,
 }
 
-pub async fn resolve(ident: EntryIdentifier, answer: ContextMenuOption) -> ContextMenuEffect {
+pub async fn resolve(
+    ident: EntryIdentifier,
+    answer: ContextMenuOption,
+    state: &mut RSState,
+) -> ContextMenuEffect {
     match answer {
         ContextMenuOption::Move => ContextMenuEffect::MoveEntry,
         ContextMenuOption::MoveToEntry(entry, _) => {
-            DISPATCH
-                .event(Action::MoveEntryToParent(ident, entry))
+            state
+                .change(vec![Action::MoveEntryToParent(ident, entry)])
                 .await;
             ContextMenuEffect::None
         }
-        ContextMenuOption::ChangeCardProfile(name, _) => {
-            DISPATCH.event(Action::ChangeCardProfile(ident, name)).await;
+        ContextMenuOption::ChangeCardProfile(name, _, _) => {
+            state
+                .change(vec![Action::ChangeCardProfile(ident, name)])
+                .await;
+            ContextMenuEffect::None
+        }
+        ContextMenuOption::SetPort(name, _) => {
+            DISPATCH.event(Action::SetPort(ident, name)).await;
             ContextMenuEffect::None
         }
         ContextMenuOption::Suspend => {
-            DISPATCH.event(Action::SetSuspend(ident, true)).await;
+            state.change(vec![Action::SetSuspend(ident, true)]).await;
             ContextMenuEffect::None
         }
         ContextMenuOption::Resume => {
-            DISPATCH.event(Action::SetSuspend(ident, false)).await;
+            state.change(vec![Action::SetSuspend(ident, false)]).await;
             ContextMenuEffect::None
         }
         ContextMenuOption::Kill => {
             DISPATCH.event(Action::KillEntry(ident)).await;
             ContextMenuEffect::None
         }
-        _ => ContextMenuEffect::None,
+        ContextMenuOption::SetAsDefault(name) => {
+            DISPATCH.event(Action::SetDefault(ident, name)).await;
+            ContextMenuEffect::None
+        }
     }
 }
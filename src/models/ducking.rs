@@ -0,0 +1,21 @@
+use crate::{entry::EntryIdentifier, VARIABLES};
+
+use std::collections::HashMap;
+
+use pulse::volume::ChannelVolumes;
+
+#[derive(Default)]
+pub struct Ducking {
+    // follows the config until it's toggled in the UI
+    pub enabled: Option<bool>,
+    // a call is going on and other streams are lowered
+    pub active: bool,
+    // lowered streams, with their volumes from before and after lowering them
+    pub ducked: HashMap<EntryIdentifier, (ChannelVolumes, ChannelVolumes)>,
+}
+
+impl Ducking {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.unwrap_or_else(|| VARIABLES.get().ducking)
+    }
+}
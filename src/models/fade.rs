@@ -0,0 +1,94 @@
+use super::Action;
+
+use crate::entry::EntryIdentifier;
+
+use std::time::Duration;
+
+use pulse::volume::{ChannelVolumes, Volume, VolumeDB};
+
+// logarithmic fades to and from silence start or end here instead of -inf dB
+static MIN_DB: f64 = -60.0;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum FadeCurve {
+    // the same number of percentage points every step
+    Linear,
+    // the same number of decibels every step, which sounds more even
+    Log,
+}
+
+// volume of an entry moving to another level, run by the PulseAudio loop
+#[derive(Clone, PartialEq, Debug)]
+pub struct Fade {
+    pub ident: EntryIdentifier,
+    pub from: ChannelVolumes,
+    pub to: ChannelVolumes,
+    pub duration: Duration,
+    pub curve: FadeCurve,
+    // mute the entry once it's silent and put the volume back, for soft mute
+    pub mute: bool,
+}
+
+impl FadeCurve {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "linear" => Some(Self::Linear),
+            "log" => Some(Self::Log),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Linear => "linear",
+            Self::Log => "log",
+        }
+    }
+
+    fn interpolate(&self, from: Volume, to: Volume, t: f64) -> Volume {
+        if t >= 1.0 {
+            return to;
+        }
+
+        match self {
+            Self::Linear => Volume((from.0 as f64 + (to.0 as f64 - from.0 as f64) * t) as u32),
+            Self::Log => {
+                let db = |v: Volume| VolumeDB::from(v).0.max(MIN_DB);
+                let (a, b) = (db(from), db(to));
+                Volume::from(VolumeDB(a + (b - a) * t))
+            }
+        }
+    }
+}
+
+impl Fade {
+    pub fn volume_at(&self, elapsed: Duration) -> ChannelVolumes {
+        let t = if self.duration.as_millis() == 0 {
+            1.0
+        } else {
+            elapsed.as_secs_f64() / self.duration.as_secs_f64()
+        };
+
+        let mut vols = self.to;
+        for (v, (from, to)) in vols
+            .get_mut()
+            .iter_mut()
+            .zip(self.from.get().iter().zip(self.to.get()))
+        {
+            *v = self.curve.interpolate(*from, *to, t);
+        }
+        vols
+    }
+
+    // sent after the last step
+    pub fn finish(&self) -> Vec<Action> {
+        if self.mute {
+            vec![
+                Action::MuteEntry(self.ident, true),
+                Action::SetVolume(self.ident, self.from),
+            ]
+        } else {
+            Vec::new()
+        }
+    }
+}
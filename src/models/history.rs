@@ -0,0 +1,212 @@
+use super::Action;
+
+use crate::entry::{Entries, EntryIdentifier, EntryType};
+
+use std::time::{Duration, Instant};
+
+use pulse::volume::ChannelVolumes;
+
+// oldest changes are forgotten after that
+static MAX_EDITS: usize = 100;
+// volume changes of one entry closer together than this are undone at once,
+// so holding a key or dragging a volume bar doesn't fill the history
+static MERGE_TIME: Duration = Duration::from_secs(1);
+
+#[derive(Clone, Debug)]
+enum Value {
+    Volume(ChannelVolumes),
+    Mute(bool),
+    Parent(EntryIdentifier),
+    Profile(String),
+    Suspend(bool),
+}
+
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            // values past the number of channels are garbage
+            (Value::Volume(a), Value::Volume(b)) => a.get() == b.get(),
+            (Value::Mute(a), Value::Mute(b)) => a == b,
+            (Value::Parent(a), Value::Parent(b)) => a == b,
+            (Value::Profile(a), Value::Profile(b)) => a == b,
+            (Value::Suspend(a), Value::Suspend(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Change {
+    ident: EntryIdentifier,
+    before: Value,
+    after: Value,
+}
+
+// everything changed by one user action, like restoring a scene
+struct Edit {
+    changes: Vec<Change>,
+    at: Instant,
+}
+
+#[derive(Default)]
+pub struct History {
+    undo: Vec<Edit>,
+    redo: Vec<Edit>,
+}
+
+impl History {
+    // remembers what actions sent to PulseAudio are about to change
+    pub fn record(&mut self, actions: &[Action], entries: &Entries) {
+        let changes = actions
+            .iter()
+            .filter_map(|action| Change::new(action, entries))
+            .filter(|c| c.before != c.after)
+            .collect::<Vec<_>>();
+        if changes.is_empty() {
+            return;
+        }
+
+        self.redo.clear();
+
+        let now = Instant::now();
+        if let Some(last) = self.undo.last_mut() {
+            if last.merges_with(&changes, now) {
+                last.changes[0].after = changes[0].after.clone();
+                last.at = now;
+                if last.changes[0].before == last.changes[0].after {
+                    self.undo.pop();
+                }
+                return;
+            }
+        }
+
+        self.undo.push(Edit { changes, at: now });
+        if self.undo.len() > MAX_EDITS {
+            self.undo.remove(0);
+        }
+    }
+
+    // actions reverting the last edit. Entries changed by someone else since then
+    // are left alone, edits with nothing left to revert are skipped
+    pub fn undo(&mut self, entries: &Entries) -> Vec<Action> {
+        while let Some(edit) = self.undo.pop() {
+            let reverted = edit
+                .changes
+                .into_iter()
+                .rev()
+                .filter(|c| c.current(entries).as_ref() == Some(&c.after))
+                .collect::<Vec<_>>();
+            if reverted.is_empty() {
+                continue;
+            }
+
+            let actions = reverted.iter().map(|c| c.action(&c.before)).collect();
+            self.redo.push(Edit {
+                changes: reverted.into_iter().rev().collect(),
+                at: Instant::now(),
+            });
+            return actions;
+        }
+        Vec::new()
+    }
+
+    pub fn redo(&mut self, entries: &Entries) -> Vec<Action> {
+        while let Some(edit) = self.redo.pop() {
+            let changes = edit
+                .changes
+                .into_iter()
+                .filter(|c| c.current(entries).as_ref() == Some(&c.before))
+                .collect::<Vec<_>>();
+            if changes.is_empty() {
+                continue;
+            }
+
+            let actions = changes.iter().map(|c| c.action(&c.after)).collect();
+            // a redone edit is never merged with what comes after it
+            self.undo.push(Edit {
+                changes,
+                at: Instant::now() - MERGE_TIME,
+            });
+            return actions;
+        }
+        Vec::new()
+    }
+}
+
+impl Edit {
+    fn merges_with(&self, changes: &[Change], now: Instant) -> bool {
+        match (self.changes.as_slice(), changes) {
+            ([last], [change]) => {
+                last.ident == change.ident
+                    && matches!(
+                        (&last.after, &change.before),
+                        (Value::Volume(_), Value::Volume(_))
+                    )
+                    && now.duration_since(self.at) < MERGE_TIME
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Change {
+    fn new(action: &Action, entries: &Entries) -> Option<Self> {
+        let (ident, after) = match action {
+            Action::SetVolume(ident, vols) => (*ident, Value::Volume(*vols)),
+            // fades are remembered by where they end
+            Action::Fade(fade) if fade.mute => (fade.ident, Value::Mute(true)),
+            Action::Fade(fade) => (fade.ident, Value::Volume(fade.to)),
+            Action::MuteEntry(ident, mute) => (*ident, Value::Mute(*mute)),
+            Action::MoveEntryToParent(ident, parent) => (*ident, Value::Parent(*parent)),
+            Action::ChangeCardProfile(ident, profile) => (*ident, Value::Profile(profile.clone())),
+            Action::SetSuspend(ident, suspend) => (*ident, Value::Suspend(*suspend)),
+            _ => return None,
+        };
+
+        let mut change = Self {
+            ident,
+            before: after.clone(),
+            after,
+        };
+        change.before = change.current(entries)?;
+
+        Some(change)
+    }
+
+    // value of the same kind as `after`, as the entry has it now
+    fn current(&self, entries: &Entries) -> Option<Value> {
+        let entry = entries.get(&self.ident)?;
+
+        if let Value::Profile(_) = self.after {
+            let card = entry.card_entry.as_ref()?;
+            let profile = card.profiles.get(card.selected_profile?)?;
+            return Some(Value::Profile(profile.name.clone()));
+        }
+
+        let play = entry.play_entry.as_ref()?;
+        Some(match self.after {
+            Value::Volume(_) => Value::Volume(play.volume),
+            Value::Mute(_) => Value::Mute(play.mute),
+            Value::Parent(_) => {
+                let parent_type = match entry.entry_type {
+                    EntryType::SinkInput => EntryType::Sink,
+                    _ => EntryType::Source,
+                };
+                Value::Parent(EntryIdentifier::new(parent_type, entry.parent?))
+            }
+            Value::Suspend(_) => Value::Suspend(play.suspended),
+            Value::Profile(_) => return None,
+        })
+    }
+
+    fn action(&self, value: &Value) -> Action {
+        let ident = self.ident;
+        match value.clone() {
+            Value::Volume(vols) => Action::SetVolume(ident, vols),
+            Value::Mute(mute) => Action::MuteEntry(ident, mute),
+            Value::Parent(parent) => Action::MoveEntryToParent(ident, parent),
+            Value::Profile(profile) => Action::ChangeCardProfile(ident, profile),
+            Value::Suspend(suspend) => Action::SetSuspend(ident, suspend),
+        }
+    }
+}
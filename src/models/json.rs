@@ -0,0 +1,211 @@
+use super::{target::ALL_TYPES, RSState};
+
+use crate::entry::{volume_to_percent, Entry, EntryIdentifier, EntryType};
+
+use pulse::channelmap::Position;
+
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct JsonEntry<'a> {
+    #[serde(rename = "type")]
+    entry_type: &'static str,
+    index: u32,
+    name: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    device_name: Option<&'a str>,
+    parent: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    default: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mute: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    volume: Option<Vec<JsonChannel>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    suspended: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    monitor_source: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ports: Option<Vec<JsonPort<'a>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    active_port: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    profiles: Option<Vec<JsonProfile<'a>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    active_profile: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+pub struct JsonChannel {
+    channel: String,
+    percent: u32,
+    // raw PulseAudio volume, 65536 is 100%
+    value: u32,
+}
+
+#[derive(Serialize)]
+pub struct JsonProfile<'a> {
+    name: &'a str,
+    description: &'a str,
+    available: bool,
+    sinks: u32,
+    sources: u32,
+    priority: u32,
+}
+
+#[derive(Serialize)]
+pub struct JsonPort<'a> {
+    name: &'a str,
+    description: &'a str,
+    available: bool,
+}
+
+// answer to "state" request sent to the control socket
+#[derive(Serialize)]
+pub struct JsonState<'a> {
+    page: &'static str,
+    selected: Option<JsonIdentifier>,
+    entries: Vec<JsonEntry<'a>>,
+}
+
+#[derive(Serialize)]
+pub struct JsonIdentifier {
+    #[serde(rename = "type")]
+    entry_type: &'static str,
+    index: u32,
+}
+
+// one line of `list --watch` output
+#[derive(Serialize)]
+#[serde(tag = "event", rename_all = "lowercase")]
+pub enum JsonEvent<'a> {
+    Update {
+        entry: JsonEntry<'a>,
+    },
+    Removed {
+        #[serde(rename = "type")]
+        entry_type: &'static str,
+        index: u32,
+    },
+}
+
+// every entry, in the order `rsmixer list` prints them
+pub fn json_entries(state: &RSState) -> Vec<JsonEntry<'_>> {
+    ALL_TYPES
+        .iter()
+        .flat_map(|t| state.entries.iter_type(*t))
+        .map(|(_, entry)| JsonEntry::new(state, entry))
+        .collect()
+}
+
+impl<'a> JsonEntry<'a> {
+    pub fn new(state: &'a RSState, entry: &'a Entry) -> Self {
+        let mut json = Self {
+            entry_type: entry.entry_type.as_str(),
+            index: entry.index,
+            name: &entry.name,
+            device_name: None,
+            parent: entry.parent,
+            default: None,
+            mute: None,
+            volume: None,
+            suspended: None,
+            monitor_source: None,
+            ports: None,
+            active_port: None,
+            profiles: None,
+            active_profile: None,
+        };
+
+        if let Some(play) = &entry.play_entry {
+            let volume = play
+                .volume
+                .get()
+                .iter()
+                .enumerate()
+                .map(|(c, vol)| JsonChannel {
+                    channel: match play.channel_map.get().get(c) {
+                        Some(&pos) => Position::to_pretty_string(pos).unwrap_or_default(),
+                        None => String::new(),
+                    },
+                    percent: volume_to_percent(*vol),
+                    value: vol.0,
+                })
+                .collect();
+
+            json.device_name = play.device_name.as_deref();
+            json.default = match entry.entry_type {
+                EntryType::Sink | EntryType::Source => Some(state.is_default(entry)),
+                _ => None,
+            };
+            json.mute = Some(play.mute);
+            json.volume = Some(volume);
+            json.suspended = Some(play.suspended);
+            json.monitor_source = entry.monitor_source(&state.entries);
+
+            if !play.ports.is_empty() {
+                let ports = play
+                    .ports
+                    .iter()
+                    .map(|p| JsonPort {
+                        name: &p.name,
+                        description: &p.description,
+                        available: p.available,
+                    })
+                    .collect();
+
+                json.ports = Some(ports);
+                json.active_port = play.active_port.map(|i| play.ports[i].name.as_str());
+            }
+        }
+
+        if let Some(card) = &entry.card_entry {
+            let profiles = card
+                .profiles
+                .iter()
+                .map(|p| JsonProfile {
+                    name: &p.name,
+                    description: &p.description,
+                    available: p.available,
+                    sinks: p.sinks,
+                    sources: p.sources,
+                    priority: p.priority,
+                })
+                .collect();
+
+            json.profiles = Some(profiles);
+            json.active_profile = card
+                .selected_profile
+                .map(|i| card.profiles[i].name.as_str());
+        }
+
+        json
+    }
+}
+
+impl<'a> JsonEvent<'a> {
+    pub fn removed(ident: EntryIdentifier) -> Self {
+        Self::Removed {
+            entry_type: ident.entry_type.as_str(),
+            index: ident.index,
+        }
+    }
+}
+
+impl<'a> JsonState<'a> {
+    pub fn new(state: &'a RSState) -> Self {
+        let selected = state
+            .page_entries
+            .get(state.selected)
+            .map(|ident| JsonIdentifier {
+                entry_type: ident.entry_type.as_str(),
+                index: ident.index,
+            });
+
+        Self {
+            page: state.current_page.as_str(),
+            selected,
+            entries: json_entries(state),
+        }
+    }
+}
@@ -1,15 +1,31 @@
 pub mod actions;
 pub mod context_menus;
+mod ducking;
+mod fade;
+mod history;
+pub mod json;
+mod monitor_target;
 mod page_entries;
 mod page_type;
+mod peak_table;
 mod redraw_type;
+mod scene;
 mod state;
+pub mod target;
+#[cfg(test)]
+mod tests;
 mod ui_mode;
 
 pub use self::state::RSState;
 pub use actions::Action;
 pub use context_menus::{ContextMenuEffect, ContextMenuOption};
+pub use ducking::Ducking;
+pub use fade::{Fade, FadeCurve};
+pub use history::History;
+pub use monitor_target::MonitorTarget;
 pub use page_entries::PageEntries;
 pub use page_type::PageType;
+pub use peak_table::PeakTable;
 pub use redraw_type::RedrawType;
+pub use scene::Scene;
 pub use ui_mode::UIMode;
@@ -0,0 +1,10 @@
+use pulse::channelmap::Map;
+
+// what a peak meter of an entry records
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct MonitorTarget {
+    // index of the source to record from
+    pub source: Option<u32>,
+    // channels metered separately, None for a single mono meter
+    pub channels: Option<Map>,
+}
@@ -1,6 +1,6 @@
 use crate::{
     entry::{EntryIdentifier, EntrySpaceLvl, EntryType},
-    ui::util::{entry_height, Rect},
+    ui::util::{entry_height, terminal_size, Rect},
     RSError,
 };
 
@@ -8,6 +8,8 @@ pub struct PageEntries {
     pub entries: Vec<EntryIdentifier>,
     pub last_term_h: u16,
     pub lvls: Vec<EntrySpaceLvl>,
+    // additional height of expanded entries
+    pub extra_heights: Vec<u16>,
     pub visibility: Vec<usize>,
 }
 
@@ -17,6 +19,7 @@ impl PageEntries {
             entries: Vec::new(),
             last_term_h: 0,
             lvls: Vec::new(),
+            extra_heights: Vec::new(),
             visibility: Vec::new(),
         }
     }
@@ -37,6 +40,10 @@ impl PageEntries {
         }
     }
 
+    pub fn height(&self, i: usize) -> u16 {
+        entry_height(self.lvls[i]) + self.extra_heights.get(i).copied().unwrap_or(0)
+    }
+
     pub fn reflow_scroll(&mut self, h: u16, force: bool) {
         if !force && h == self.last_term_h {
             return;
@@ -49,11 +56,9 @@ impl PageEntries {
         let mut current_scroll_page = 0;
         let mut current_height = 0;
 
-        self.visibility = self
-            .lvls
-            .iter()
-            .map(|&e| {
-                current_height += entry_height(e);
+        self.visibility = (0..self.lvls.len())
+            .map(|i| {
+                current_height += self.height(i);
 
                 if current_height > h {
                     current_scroll_page += 1;
@@ -66,7 +71,7 @@ impl PageEntries {
     }
 
     pub fn is_entry_visible(&self, index: usize, scroll: usize) -> Result<Option<Rect>, RSError> {
-        let (w, _) = crossterm::terminal::size()?;
+        let (w, _) = terminal_size()?;
 
         if self.visibility[index] != scroll {
             return Ok(None);
@@ -75,16 +80,11 @@ impl PageEntries {
         let mut he = 0;
         for i in 0..index {
             if self.visibility[i] == scroll {
-                he += entry_height(self.lvls[i]);
+                he += self.height(i);
             }
         }
 
-        Ok(Some(Rect::new(
-            2,
-            2 + he,
-            w - 4,
-            entry_height(self.lvls[index]),
-        )))
+        Ok(Some(Rect::new(2, 2 + he, w - 4, self.height(index))))
     }
 
     pub fn visible_range_with_lvl<'a>(
@@ -128,6 +128,17 @@ impl PageEntries {
 
         ret
     }
+
+    pub fn set_extra_heights(&mut self, extra_heights: Vec<u16>) -> bool {
+        if extra_heights == self.extra_heights {
+            return false;
+        }
+
+        self.extra_heights = extra_heights;
+        self.reflow_scroll(self.last_term_h, true);
+
+        true
+    }
 }
 
 fn calc_lvl(parent_type: EntryType, vs: &[EntryIdentifier], index: usize) -> EntrySpaceLvl {
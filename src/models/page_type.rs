@@ -7,11 +7,20 @@ use crate::{
 
 use std::{fmt::Display, iter};
 
+// in the order they're shown in the title
+static PAGES: [PageType; 4] = [
+    PageType::Output,
+    PageType::Input,
+    PageType::Cards,
+    PageType::Scenes,
+];
+
 #[derive(PartialEq, Clone, Hash, Copy, Debug)]
 pub enum PageType {
     Output,
     Input,
     Cards,
+    Scenes,
 }
 impl Eq for PageType {}
 impl Display for PageType {
@@ -25,16 +34,18 @@ impl From<PageType> for i8 {
             PageType::Output => 0,
             PageType::Input => 1,
             PageType::Cards => 2,
+            PageType::Scenes => 3,
         }
     }
 }
 impl From<i8> for PageType {
     fn from(p: i8) -> PageType {
         match p {
-            -1 => PageType::Cards,
+            -1 => PageType::Scenes,
             0 => PageType::Output,
             1 => PageType::Input,
             2 => PageType::Cards,
+            3 => PageType::Scenes,
             _ => PageType::Output,
         }
     }
@@ -45,8 +56,17 @@ impl PageType {
             PageType::Output => "Output",
             PageType::Input => "Input",
             PageType::Cards => "Cards",
+            PageType::Scenes => "Scenes",
         }
     }
+    // length of the title drawn by as_styled_string
+    pub fn title_len() -> u16 {
+        PAGES
+            .iter()
+            .map(|pt| pt.as_str().len() as u16 + 3)
+            .sum::<u16>()
+            - 3
+    }
     pub fn as_styled_string(&self) -> String {
         let styled_name = |pt: PageType| {
             if pt == *self {
@@ -56,24 +76,46 @@ impl PageType {
             }
         };
 
-        let divider = get_style("muted").apply(" / ");
+        let divider = get_style("muted").apply(" / ").to_string();
 
-        format!(
-            "{}{}{}{}{}",
-            styled_name(PageType::Output),
-            divider.clone(),
-            styled_name(PageType::Input),
-            divider.clone(),
-            styled_name(PageType::Cards)
-        )
+        PAGES
+            .iter()
+            .map(|pt| styled_name(*pt).to_string())
+            .collect::<Vec<_>>()
+            .join(&divider)
+    }
+    // page whose name is at column x of the title drawn by as_styled_string,
+    // which starts right after the corner of the border
+    pub fn at_title_column(x: u16) -> Option<PageType> {
+        let mut start = 1;
+        for pt in &PAGES {
+            let end = start + pt.as_str().len() as u16;
+            if x >= start && x < end {
+                return Some(*pt);
+            }
+            start = end + 3;
+        }
+        None
     }
     pub fn generate_page<'a>(
         &'a self,
         entries: &'a Entries,
         ui_mode: &'a UIMode,
+        search: &'a str,
     ) -> Box<dyn Iterator<Item = (&EntryIdentifier, &Entry)> + 'a> {
+        let matches = move |e: &Entry| search.is_empty() || e.matches(search);
+
+        // scenes aren't entries, they're drawn from RSState::scenes
+        if *self == PageType::Scenes {
+            return Box::new(iter::empty());
+        }
+
         if *self == PageType::Cards {
-            return Box::new(entries.iter_type(EntryType::Card));
+            return Box::new(
+                entries
+                    .iter_type(EntryType::Card)
+                    .filter(move |(_, e)| matches(e)),
+            );
         }
 
         let (parent, child) = parent_child_types(*self);
@@ -99,17 +141,20 @@ impl PageType {
             );
         }
 
+        let children = move |ident: &'a EntryIdentifier| {
+            entries.iter_type(child).filter(move |(_, e)| {
+                e.parent == Some(ident.index) && e.hidden != HiddenStatus::Hidden && matches(e)
+            })
+        };
+
+        // while searching, parents are kept when one of their children matches
         Box::new(
             entries
                 .iter_type(parent)
-                .map(move |(ident, entry)| {
-                    std::iter::once((ident, entry)).chain(entries.iter_type(child).filter(
-                        move |(_, e)| {
-                            e.parent == Some(ident.index) && e.hidden != HiddenStatus::Hidden
-                        },
-                    ))
-                })
-                .flatten(),
+                .filter(move |(ident, entry)| matches(entry) || children(ident).next().is_some())
+                .flat_map(move |(ident, entry)| {
+                    std::iter::once((ident, entry)).chain(children(ident))
+                }),
         )
     }
 }
@@ -0,0 +1,102 @@
+use crate::entry::EntryIdentifier;
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, AtomicU32, Ordering},
+        Arc,
+    },
+};
+
+use arc_swap::ArcSwap;
+
+// Peaks of every monitored entry. Monitors write into their own slot without
+// locking and the render tick picks up the loudest peaks since the last frame
+pub struct PeakTable {
+    slots: ArcSwap<HashMap<EntryIdentifier, Arc<PeakSlot>>>,
+}
+
+pub struct PeakSlot {
+    // bits of the loudest f32 since the last frame, one per metered channel.
+    // Bits of non-negative floats compare the same way as the floats do
+    values: Vec<AtomicU32>,
+    changed: AtomicBool,
+}
+
+impl PeakSlot {
+    pub fn store(&self, peaks: &[f32]) {
+        for (value, peak) in self.values.iter().zip(peaks) {
+            value.fetch_max(peak.max(0.0).to_bits(), Ordering::Relaxed);
+        }
+        self.changed.store(true, Ordering::Release);
+    }
+
+    fn take(&self) -> Option<Vec<f32>> {
+        if !self.changed.swap(false, Ordering::Acquire) {
+            return None;
+        }
+        Some(
+            self.values
+                .iter()
+                .map(|v| f32::from_bits(v.swap(0, Ordering::Relaxed)))
+                .collect(),
+        )
+    }
+}
+
+impl Default for PeakTable {
+    fn default() -> Self {
+        Self {
+            slots: ArcSwap::from_pointee(HashMap::new()),
+        }
+    }
+}
+
+impl PeakTable {
+    // slot for a new monitor, replaces the previous one of that entry
+    pub fn insert(&self, ident: EntryIdentifier, channels: usize) -> Arc<PeakSlot> {
+        let slot = Arc::new(PeakSlot {
+            values: (0..channels.max(1)).map(|_| AtomicU32::new(0)).collect(),
+            changed: AtomicBool::new(false),
+        });
+
+        self.slots.rcu(|slots| {
+            let mut slots = HashMap::clone(slots);
+            slots.insert(ident, Arc::clone(&slot));
+            slots
+        });
+
+        slot
+    }
+
+    // writes peaks like a monitor of the entry would
+    #[cfg(test)]
+    pub fn store(&self, ident: EntryIdentifier, peaks: &[f32]) {
+        let slot = match self.slots.load().get(&ident) {
+            Some(slot) if slot.values.len() == peaks.len() => Arc::clone(slot),
+            _ => self.insert(ident, peaks.len()),
+        };
+        slot.store(peaks);
+    }
+
+    pub fn remove(&self, ident: EntryIdentifier) {
+        self.slots.rcu(|slots| {
+            let mut slots = HashMap::clone(slots);
+            slots.remove(&ident);
+            slots
+        });
+    }
+
+    pub fn clear(&self) {
+        self.slots.store(Arc::new(HashMap::new()));
+    }
+
+    // peaks written since the last call
+    pub fn take_changed(&self) -> Vec<(EntryIdentifier, Vec<f32>)> {
+        self.slots
+            .load()
+            .iter()
+            .filter_map(|(ident, slot)| slot.take().map(|peaks| (*ident, peaks)))
+            .collect()
+    }
+}
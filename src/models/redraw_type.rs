@@ -8,7 +8,7 @@ pub enum RedrawType {
     Full,
     Entries,
     PartialEntries(HashSet<usize>),
-    PeakVolume(EntryIdentifier),
+    PeakVolumes(HashSet<EntryIdentifier>),
     ContextMenu,
     None,
 }
@@ -22,7 +22,7 @@ impl From<RedrawType> for u32 {
             RedrawType::Entries => 500,
             RedrawType::ContextMenu => 500,
             RedrawType::PartialEntries(_) => 400,
-            RedrawType::PeakVolume(_) => 100,
+            RedrawType::PeakVolumes(_) => 100,
             RedrawType::None => 1,
         }
     }
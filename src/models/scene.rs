@@ -0,0 +1,213 @@
+use super::{Action, RSState};
+
+use crate::entry::{parse_entry_type, Entry, EntryIdentifier, EntryKey, EntryType};
+
+use std::collections::HashSet;
+
+use pulse::volume::Volume;
+
+use serde::{Deserialize, Serialize};
+
+// order of restoring, cards can add and remove sinks and sources
+// and those have to exist before streams are moved to them
+static SCENE_TYPES: [EntryType; 5] = [
+    EntryType::Card,
+    EntryType::Sink,
+    EntryType::Source,
+    EntryType::SinkInput,
+    EntryType::SourceOutput,
+];
+
+// snapshot of the whole mixer. Entries are remembered by their keys,
+// because indexes change every time a device or a stream comes back
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct Scene {
+    pub default_sink: Option<String>,
+    pub default_source: Option<String>,
+    #[serde(default)]
+    pub entries: Vec<SceneEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SceneEntry {
+    #[serde(rename = "type")]
+    pub entry_type: String,
+    pub name: String,
+    // raw PulseAudio volume of every channel
+    #[serde(default)]
+    pub volume: Vec<u32>,
+    #[serde(default)]
+    pub mute: bool,
+    // device name of the sink/source a stream plays to
+    pub target: Option<String>,
+    // active profile of a card
+    pub profile: Option<String>,
+}
+
+impl Scene {
+    pub fn capture(state: &RSState) -> Self {
+        let mut seen = HashSet::new();
+        let mut entries = Vec::new();
+
+        for entry_type in SCENE_TYPES.iter() {
+            for (_, entry) in state.entries.iter_type(*entry_type) {
+                // streams of the same application can't be told apart, the first one counts
+                if seen.insert(entry.key()) {
+                    entries.push(SceneEntry::capture(entry, state));
+                }
+            }
+        }
+
+        Self {
+            default_sink: state.default_sink.clone(),
+            default_source: state.default_source.clone(),
+            entries,
+        }
+    }
+
+    // actions that bring the mixer back to this scene, entries that already
+    // look like they did when it was saved and ones that are gone are skipped
+    pub fn restore(&self, state: &RSState) -> Vec<Action> {
+        let mut actions = Vec::new();
+
+        for saved in &self.entries {
+            let key = match parse_entry_type(&saved.entry_type) {
+                Some(entry_type) => EntryKey {
+                    entry_type,
+                    name: saved.name.clone(),
+                },
+                None => continue,
+            };
+
+            for (ident, entry) in state.entries.iter_type(key.entry_type) {
+                if entry.key() == key {
+                    saved.restore(*ident, entry, state, &mut actions);
+                }
+            }
+        }
+
+        for (entry_type, name, current) in &[
+            (EntryType::Sink, &self.default_sink, &state.default_sink),
+            (
+                EntryType::Source,
+                &self.default_source,
+                &state.default_source,
+            ),
+        ] {
+            let name = match name {
+                Some(name) if current.as_ref() != Some(name) => name,
+                _ => continue,
+            };
+            if let Some(ident) = find_device(state, *entry_type, Some(name)) {
+                actions.push(Action::SetDefault(ident, name.clone()));
+            }
+        }
+
+        actions
+    }
+}
+
+impl SceneEntry {
+    fn capture(entry: &Entry, state: &RSState) -> Self {
+        let mut saved = Self {
+            entry_type: entry.entry_type.as_str().to_string(),
+            name: entry.key().name,
+            volume: Vec::new(),
+            mute: false,
+            target: None,
+            profile: None,
+        };
+
+        if let Some(play) = &entry.play_entry {
+            saved.volume = play.volume.get().iter().map(|v| v.0).collect();
+            saved.mute = play.mute;
+            saved.target = parent_type(entry.entry_type)
+                .zip(entry.parent)
+                .and_then(|(t, index)| state.entries.get(&EntryIdentifier::new(t, index)))
+                .and_then(|parent| parent.play_entry.as_ref())
+                .and_then(|p| p.device_name.clone());
+        }
+
+        if let Some(card) = &entry.card_entry {
+            saved.profile = card
+                .selected_profile
+                .and_then(|i| card.profiles.get(i))
+                .map(|p| p.name.clone());
+        }
+
+        saved
+    }
+
+    fn restore(
+        &self,
+        ident: EntryIdentifier,
+        entry: &Entry,
+        state: &RSState,
+        actions: &mut Vec<Action>,
+    ) {
+        if let (Some(card), Some(profile)) = (&entry.card_entry, &self.profile) {
+            let active = card.selected_profile.and_then(|i| card.profiles.get(i));
+            if active.map(|p| &p.name) != Some(profile)
+                && card.profiles.iter().any(|p| p.name == *profile)
+            {
+                actions.push(Action::ChangeCardProfile(ident, profile.clone()));
+            }
+        }
+
+        let play = match &entry.play_entry {
+            Some(play) => play,
+            None => return,
+        };
+
+        if !self.volume.is_empty() {
+            let mut vols = play.volume;
+            if vols.len() as usize == self.volume.len() {
+                for (v, saved) in vols.get_mut().iter_mut().zip(&self.volume) {
+                    *v = Volume(*saved);
+                }
+            } else {
+                // the channel map changed, keep the loudest channel
+                vols.scale(Volume(self.volume.iter().copied().max().unwrap_or(0)));
+            }
+            if vols.get() != play.volume.get() {
+                actions.push(Action::SetVolume(ident, vols));
+            }
+        }
+
+        if self.mute != play.mute {
+            actions.push(Action::MuteEntry(ident, self.mute));
+        }
+
+        if let Some(parent_type) = parent_type(entry.entry_type) {
+            if let Some(parent) = find_device(state, parent_type, self.target.as_deref()) {
+                if entry.parent != Some(parent.index) {
+                    actions.push(Action::MoveEntryToParent(ident, parent));
+                }
+            }
+        }
+    }
+}
+
+fn parent_type(entry_type: EntryType) -> Option<EntryType> {
+    match entry_type {
+        EntryType::SinkInput => Some(EntryType::Sink),
+        EntryType::SourceOutput => Some(EntryType::Source),
+        _ => None,
+    }
+}
+
+fn find_device(
+    state: &RSState,
+    entry_type: EntryType,
+    device_name: Option<&str>,
+) -> Option<EntryIdentifier> {
+    let device_name = device_name?;
+
+    state
+        .entries
+        .iter_type(entry_type)
+        .find(|(_, e)| {
+            e.play_entry.as_ref().and_then(|p| p.device_name.as_deref()) == Some(device_name)
+        })
+        .map(|(ident, _)| *ident)
+}
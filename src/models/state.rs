@@ -1,10 +1,20 @@
-use super::{ContextMenuOption, PageEntries, PageType, RedrawType, UIMode};
+use super::{
+    Action, ContextMenuOption, Ducking, History, PageEntries, PageType, RedrawType, Scene, UIMode,
+};
 
 use crate::{
-    entry::Entries,
+    config::limit_volume,
+    entry::{Entries, Entry, EntryIdentifier, EntryKey, EntryType, HiddenStatus},
     ui::{page::UIPage, util::Rect},
+    DISPATCH, VARIABLES,
 };
 
+use std::{cmp::min, collections::HashSet, time::Instant};
+
+use linked_hash_map::LinkedHashMap;
+
+use pulse::volume::ChannelVolumes;
+
 pub struct RSState {
     pub current_page: PageType,
     pub entries: Entries,
@@ -16,6 +26,219 @@ pub struct RSState {
     pub redraw: RedrawType,
     pub ui_mode: UIMode,
     pub ui_page: UIPage,
+    pub default_sink: Option<String>,
+    pub default_source: Option<String>,
+    pub expanded: HashSet<EntryIdentifier>,
+    pub selected_channel: Option<usize>,
+    pub input_exact_volume: String,
+    // entries not matching it are filtered out of the page, empty shows everything
+    pub search: String,
+    // entry whose volume bar is being dragged with the mouse
+    pub dragged: Option<EntryIdentifier>,
+    // saved scenes, read from disk every time the Scenes page is opened
+    pub scenes: LinkedHashMap<String, Scene>,
+    pub selected_scene: usize,
+    // changes made from the UI, for undo and redo
+    pub history: History,
+    // other streams are lowered while a call is going on
+    pub ducking: Ducking,
+    // UI state from before PulseAudio disconnected, waiting for entries to come back
+    pub restore: Option<RestoreState>,
+    // every entry PulseAudio had when connecting was received,
+    // the ones showing up after that are new streams
+    pub loaded: bool,
+    // scene waiting for PulseAudio to handle the first pass of restoring it
+    pub restoring_scene: Option<Scene>,
+}
+
+#[derive(Default)]
+pub struct RestoreState {
+    pub selected: Option<EntryKey>,
+    // one item for every hidden entry, streams of the same application share their key
+    pub hidden: Vec<(EntryKey, HiddenStatus)>,
+    // streams lowered for a call, so they aren't lowered again when they come back
+    pub ducked: Vec<(EntryKey, (ChannelVolumes, ChannelVolumes))>,
+}
+
+impl RestoreState {
+    pub fn is_done(&self) -> bool {
+        self.selected.is_none() && self.hidden.is_empty() && self.ducked.is_empty()
+    }
+}
+
+impl RSState {
+    // number of channel rows shown under the entry, 0 if it isn't expanded
+    pub fn channel_rows(&self, ident: &EntryIdentifier) -> usize {
+        if !self.expanded.contains(ident) {
+            return 0;
+        }
+
+        match self.entries.get(ident) {
+            Some(Entry {
+                play_entry: Some(play),
+                ..
+            }) => play.channel_rows(),
+            _ => 0,
+        }
+    }
+
+    // state that should survive reconnecting to PulseAudio, entry indexes
+    // won't be the same after that so entries are remembered by their keys
+    pub fn save_for_reconnect(&mut self) -> RestoreState {
+        let mut restore = self.restore.take().unwrap_or_default();
+
+        if let Some(entry) = self
+            .page_entries
+            .get(self.selected)
+            .and_then(|ident| self.entries.get(&ident))
+        {
+            restore.selected = Some(entry.key());
+        }
+
+        let types = [
+            EntryType::Sink,
+            EntryType::SinkInput,
+            EntryType::Source,
+            EntryType::SourceOutput,
+        ];
+        for (_, entry) in types.iter().flat_map(|t| self.entries.iter_type(*t)) {
+            if let HiddenStatus::Hidden | HiddenStatus::HiddenKids = entry.hidden {
+                restore.hidden.push((entry.key(), entry.hidden.clone()));
+            }
+        }
+
+        for (ident, vols) in &self.ducking.ducked {
+            if let Some(entry) = self.entries.get(ident) {
+                restore.ducked.push((entry.key(), *vols));
+            }
+        }
+
+        restore
+    }
+
+    // new peaks from a monitor, returns true when the peak row has to be redrawn
+    pub fn update_peaks(&mut self, ident: EntryIdentifier, peaks: &[f32], now: Instant) -> bool {
+        let changed = match self
+            .entries
+            .get_mut(&ident)
+            .and_then(|e| e.play_entry.as_mut())
+        {
+            Some(play) => play.update_peaks(peaks, now),
+            None => false,
+        };
+
+        changed && self.page_entries.iter_entries().any(|&i| i == ident)
+    }
+
+    // sends PulseAudio commands made by the user, so they can be undone.
+    // Volumes above the limit of their entry are lowered to it
+    pub async fn change(&mut self, mut actions: Vec<Action>) {
+        let limits = &VARIABLES.get().limits;
+        for action in actions.iter_mut() {
+            let (ident, vols) = match action {
+                Action::SetVolume(ident, vols) => (*ident, vols),
+                Action::Fade(fade) => (fade.ident, &mut fade.to),
+                _ => continue,
+            };
+            if let Some(entry) = self.entries.get(&ident) {
+                *vols = limit_volume(limits, entry, *vols);
+            }
+        }
+
+        self.history.record(&actions, &self.entries);
+        for action in actions {
+            DISPATCH.event(action).await;
+        }
+    }
+
+    // Restoring a scene takes two passes, sinks and sources of cards whose profile
+    // changed only exist after PulseAudio handled the first one.
+    // The second pass is done by finish_scene once Synced comes back
+    pub async fn restore_scene(&mut self, scene: Scene) {
+        let actions = scene.restore(self);
+        self.change(actions).await;
+
+        self.restoring_scene = Some(scene);
+        DISPATCH.event(Action::RequestSync).await;
+    }
+
+    pub async fn finish_scene(&mut self) {
+        if let Some(scene) = self.restoring_scene.take() {
+            let actions = scene.restore(self);
+            self.change(actions).await;
+        }
+    }
+
+    // called with every new entry, while there's something to restore
+    pub fn restore_entry(&mut self, entry: &mut Entry) {
+        if let Some(restore) = &mut self.restore {
+            let key = entry.key();
+            if let Some(i) = restore.hidden.iter().position(|(k, _)| *k == key) {
+                entry.hidden = restore.hidden.remove(i).1;
+            }
+            if let Some(i) = restore.ducked.iter().position(|(k, _)| *k == key) {
+                let ident = EntryIdentifier::new(entry.entry_type, entry.index);
+                self.ducking
+                    .ducked
+                    .insert(ident, restore.ducked.remove(i).1);
+            }
+        }
+    }
+
+    // position of the entry that was selected before reconnecting, once it's on the page
+    pub fn restore_selection(&mut self) -> Option<usize> {
+        let restore = self.restore.as_mut()?;
+        let key = restore.selected.as_ref()?;
+
+        let entries = &self.entries;
+        let i = self
+            .page_entries
+            .iter_entries()
+            .position(|ident| entries.get(ident).map(|e| e.key()).as_ref() == Some(key))?;
+
+        restore.selected = None;
+        Some(i)
+    }
+
+    // next entry on the page matching the search query, starting after `from`
+    // and wrapping around (or going backwards)
+    pub fn find_match(&self, from: usize, forward: bool) -> Option<usize> {
+        let len = self.page_entries.len();
+        if self.search.is_empty() || len == 0 {
+            return None;
+        }
+        let from = min(from, len - 1);
+
+        (1..=len)
+            .map(|step| {
+                if forward {
+                    (from + step) % len
+                } else {
+                    (from + len - step) % len
+                }
+            })
+            .find(|&i| {
+                self.page_entries
+                    .get(i)
+                    .and_then(|ident| self.entries.get(&ident))
+                    .is_some_and(|e| e.matches(&self.search))
+            })
+    }
+
+    pub fn is_default(&self, entry: &Entry) -> bool {
+        let default = match entry.entry_type {
+            EntryType::Sink => &self.default_sink,
+            EntryType::Source => &self.default_source,
+            _ => {
+                return false;
+            }
+        };
+
+        match &entry.play_entry {
+            Some(play) => play.device_name.is_some() && play.device_name == *default,
+            None => false,
+        }
+    }
 }
 
 impl Default for RSState {
@@ -33,6 +256,20 @@ impl Default for RSState {
             ui_page: UIPage {
                 inner_area: Rect::new(2, 2, 0, 0),
             },
+            default_sink: None,
+            default_source: None,
+            expanded: HashSet::new(),
+            selected_channel: None,
+            input_exact_volume: String::new(),
+            search: String::new(),
+            dragged: None,
+            scenes: LinkedHashMap::new(),
+            selected_scene: 0,
+            history: History::default(),
+            ducking: Ducking::default(),
+            restore: None,
+            loaded: false,
+            restoring_scene: None,
         }
     }
 }
@@ -0,0 +1,104 @@
+use super::RSState;
+
+use crate::{
+    entry::{parse_entry_type, Entry, EntryIdentifier, EntryType},
+    RSError,
+};
+
+pub static ALL_TYPES: [EntryType; 5] = [
+    EntryType::Sink,
+    EntryType::SinkInput,
+    EntryType::Source,
+    EntryType::SourceOutput,
+    EntryType::Card,
+];
+
+pub static PLAY_TYPES: [EntryType; 4] = [
+    EntryType::Sink,
+    EntryType::SinkInput,
+    EntryType::Source,
+    EntryType::SourceOutput,
+];
+
+// finds the entry described by target among entries of given types
+pub fn find(
+    state: &RSState,
+    target: &str,
+    types: &[EntryType],
+) -> Result<EntryIdentifier, RSError> {
+    let not_found = || RSError::TargetNotFound(target.to_string());
+
+    let (types, name) = match target {
+        "@DEFAULT_SINK@" if types.contains(&EntryType::Sink) => {
+            return find_default(state, EntryType::Sink, &state.default_sink);
+        }
+        "@DEFAULT_SOURCE@" if types.contains(&EntryType::Source) => {
+            return find_default(state, EntryType::Source, &state.default_source);
+        }
+        _ => match target
+            .split_once(':')
+            .map(|(t, n)| (parse_entry_type(t), n))
+        {
+            Some((Some(entry_type), name)) => {
+                if !types.contains(&entry_type) {
+                    return Err(not_found());
+                }
+                if let Ok(index) = name.parse::<u32>() {
+                    let ident = EntryIdentifier::new(entry_type, index);
+                    return match state.entries.get(&ident) {
+                        Some(_) => Ok(ident),
+                        None => Err(not_found()),
+                    };
+                }
+                (vec![entry_type], name)
+            }
+            _ => (types.to_vec(), target),
+        },
+    };
+
+    // every name contains an empty one
+    if name.is_empty() {
+        return Err(not_found());
+    }
+
+    let candidates = || {
+        types
+            .iter()
+            .flat_map(move |t| state.entries.iter_type(*t))
+            .map(|(ident, entry)| (*ident, entry))
+    };
+
+    // exact PulseAudio name first, then the name shown in the UI, then a part of it
+    let lowercase = name.to_lowercase();
+    let matchers: [&dyn Fn(&Entry) -> bool; 3] = [
+        &|e| device_name(e) == Some(name),
+        &|e| e.name.to_lowercase() == lowercase,
+        &|e| e.name.to_lowercase().contains(&lowercase),
+    ];
+
+    matchers
+        .iter()
+        .find_map(|matches| candidates().find(|(_, e)| matches(e)))
+        .map(|(ident, _)| ident)
+        .ok_or_else(not_found)
+}
+
+fn find_default(
+    state: &RSState,
+    entry_type: EntryType,
+    default: &Option<String>,
+) -> Result<EntryIdentifier, RSError> {
+    state
+        .entries
+        .iter_type(entry_type)
+        .find(|(_, e)| default.is_some() && device_name(e) == default.as_deref())
+        .map(|(ident, _)| *ident)
+        .ok_or_else(|| RSError::TargetNotFound(format!("default {}", entry_type.as_str())))
+}
+
+fn device_name(entry: &Entry) -> Option<&str> {
+    entry
+        .play_entry
+        .as_ref()
+        .and_then(|p| p.device_name.as_deref())
+}
@@ -0,0 +1,202 @@
+use super::{
+    json::{JsonEntry, JsonEvent, JsonState},
+    target::{find, ALL_TYPES, PLAY_TYPES},
+};
+
+use crate::{
+    entry::{percent_to_volume, EntryIdentifier, EntryType},
+    event_loop::harness::Harness,
+    pa::fake::FakeBackend,
+    Action, RSError,
+};
+
+use serde_json::json;
+
+fn ident(entry_type: EntryType, index: u32) -> EntryIdentifier {
+    EntryIdentifier::new(entry_type, index)
+}
+
+fn backend() -> FakeBackend {
+    FakeBackend::default()
+        .sink(0, "Speakers", 50)
+        .sink(1, "Speakers 2", 50)
+        .sink_input(5, "Firefox", 0, 80)
+        .source(0, "Speakers Monitor", 100)
+        .source(1, "Microphone", 100)
+        .source_output(7, "Recorder", 1, 60)
+        .default_sink(1)
+        .default_source(1)
+}
+
+#[tokio::test]
+async fn target_is_found_by_index_or_name() {
+    let h = Harness::new(backend(), 80, 24).await;
+
+    let found = |target| find(&h.state, target, &ALL_TYPES).ok();
+    assert_eq!(found("sink:1"), Some(ident(EntryType::Sink, 1)));
+    assert_eq!(found("sink-input:5"), Some(ident(EntryType::SinkInput, 5)));
+    assert_eq!(found("fake_sink.speakers"), Some(ident(EntryType::Sink, 0)));
+    assert_eq!(found("firefox"), Some(ident(EntryType::SinkInput, 5)));
+    assert_eq!(found("Record"), Some(ident(EntryType::SourceOutput, 7)));
+    assert_eq!(found("source:speakers"), Some(ident(EntryType::Source, 0)));
+}
+
+#[tokio::test]
+async fn default_targets_follow_the_server() {
+    let h = Harness::new(backend(), 80, 24).await;
+
+    let found = |target| find(&h.state, target, &PLAY_TYPES).ok();
+    assert_eq!(found("@DEFAULT_SINK@"), Some(ident(EntryType::Sink, 1)));
+    assert_eq!(found("@DEFAULT_SOURCE@"), Some(ident(EntryType::Source, 1)));
+}
+
+#[tokio::test]
+async fn ambiguous_target_prefers_the_closest_match() {
+    let h = Harness::new(backend(), 80, 24).await;
+
+    // "Speakers 2" and "Speakers Monitor" contain it too, the exact name wins
+    let found = find(&h.state, "speakers", &ALL_TYPES).unwrap();
+    assert_eq!(found, ident(EntryType::Sink, 0));
+
+    // with no exact name, entry types are searched in the given order
+    let found = find(&h.state, "speakers ", &ALL_TYPES).unwrap();
+    assert_eq!(found, ident(EntryType::Sink, 1));
+}
+
+#[tokio::test]
+async fn unknown_target_is_not_found() {
+    let h = Harness::new(backend(), 80, 24).await;
+
+    for target in &["Headphones", "sink:9", "sink:", "speaker:0"] {
+        assert!(matches!(
+            find(&h.state, target, &ALL_TYPES),
+            Err(RSError::TargetNotFound(_))
+        ));
+    }
+}
+
+#[tokio::test]
+async fn target_of_wrong_type_is_not_found() {
+    let h = Harness::new(backend(), 80, 24).await;
+
+    let sinks = [EntryType::Sink];
+    for target in &["source:1", "Microphone", "@DEFAULT_SOURCE@"] {
+        assert!(matches!(
+            find(&h.state, target, &sinks),
+            Err(RSError::TargetNotFound(_))
+        ));
+    }
+    assert!(find(&h.state, "Firefox", &[EntryType::SourceOutput]).is_err());
+}
+
+fn channels(percent: i16) -> serde_json::Value {
+    let value = percent_to_volume(percent).0;
+    json!([
+        { "channel": "Front Left", "percent": percent, "value": value },
+        { "channel": "Front Right", "percent": percent, "value": value },
+    ])
+}
+
+#[tokio::test]
+async fn state_is_serialized_with_every_entry() {
+    let backend = FakeBackend::default()
+        .sink(0, "Speakers", 50)
+        .sink_input(5, "Firefox", 0, 80)
+        .card(0, "Built-in Audio", &["output:analog-stereo", "off"], 1)
+        .ports(
+            ident(EntryType::Sink, 0),
+            &["analog-output-speaker", "analog-output-headphones"],
+        )
+        .default_sink(0);
+    let mut h = Harness::new(backend, 80, 24).await;
+    h.send(Action::MoveDown(1)).await;
+
+    let json = serde_json::to_value(JsonState::new(&h.state)).unwrap();
+
+    let profile = |name| {
+        json!({
+            "name": name,
+            "description": name,
+            "available": true,
+            "sinks": 1,
+            "sources": 0,
+            "priority": 0,
+        })
+    };
+    let port = |name| json!({ "name": name, "description": name, "available": true });
+    assert_eq!(
+        json,
+        json!({
+            "page": "Output",
+            "selected": { "type": "sink-input", "index": 5 },
+            "entries": [
+                {
+                    "type": "sink",
+                    "index": 0,
+                    "name": "Speakers",
+                    "device_name": "fake_sink.speakers",
+                    "parent": null,
+                    "default": true,
+                    "mute": false,
+                    "volume": channels(50),
+                    "suspended": false,
+                    "monitor_source": 1000,
+                    "ports": [port("analog-output-speaker"), port("analog-output-headphones")],
+                    "active_port": "analog-output-speaker",
+                },
+                {
+                    "type": "sink-input",
+                    "index": 5,
+                    "name": "Firefox",
+                    "parent": 0,
+                    "mute": false,
+                    "volume": channels(80),
+                    "suspended": false,
+                    "monitor_source": 1000,
+                },
+                {
+                    "type": "card",
+                    "index": 0,
+                    "name": "Built-in Audio",
+                    "parent": null,
+                    "profiles": [profile("output:analog-stereo"), profile("off")],
+                    "active_profile": "off",
+                },
+            ],
+        })
+    );
+}
+
+#[tokio::test]
+async fn events_are_serialized_with_their_kind() {
+    let h = Harness::new(FakeBackend::default().source(1, "Microphone", 100), 80, 24).await;
+    let microphone = h.state.entries.get(&ident(EntryType::Source, 1)).unwrap();
+
+    let update = JsonEvent::Update {
+        entry: JsonEntry::new(&h.state, microphone),
+    };
+    assert_eq!(
+        serde_json::to_value(update).unwrap(),
+        json!({
+            "event": "update",
+            "entry": {
+                "type": "source",
+                "index": 1,
+                "name": "Microphone",
+                "device_name": "fake_source.microphone",
+                "parent": null,
+                "default": false,
+                "mute": false,
+                "volume": channels(100),
+                "suspended": false,
+                "monitor_source": 1,
+            },
+        })
+    );
+
+    let removed = JsonEvent::removed(ident(EntryType::SinkInput, 5));
+    assert_eq!(
+        serde_json::to_string(&removed).unwrap(),
+        r#"{"event":"removed","type":"sink-input","index":5}"#
+    );
+}
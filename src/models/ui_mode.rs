@@ -7,5 +7,7 @@ pub enum UIMode {
     Help,
     MoveEntry(EntryIdentifier, EntryIdentifier),
     InputVolumeValue,
+    // typing the search query
+    Search,
     RetryIn(u64),
 }
@@ -1,11 +1,11 @@
-use super::common::*;
+use super::{common::*, Fades};
 
 use crate::{
     models::actions::statics::{CHANNEL_CAPACITY, PA_MESSAGE},
     SENDERS,
 };
 
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use tokio::{
     stream::StreamExt,
@@ -20,6 +20,7 @@ pub async fn start_async(
     SENDERS.register(PA_MESSAGE, sx).await;
 
     let mut interval = tokio::time::interval(Duration::from_millis(50));
+    let mut fades = Fades::default();
 
     let send = |ch: &cb_channel::Sender<PAInternal>, msg: PAInternal| -> Result<(), RSError> {
         match ch.send(msg) {
@@ -37,9 +38,14 @@ pub async fn start_async(
             r = res => {
                 if let Some(Ok(cmd)) = r {
                     if let Action::ExitSignal = cmd {
+                        for cmd in fades.finish() {
+                            send(&internal_sx, PAInternal::Command(Box::new(cmd)))?;
+                        }
                         break;
                     }
-                    internal_sx.send(PAInternal::Command(Box::new(cmd)))?;
+                    if !fades.handle(&cmd) {
+                        internal_sx.send(PAInternal::Command(Box::new(cmd)))?;
+                    }
                 }
             }
             i = info => {
@@ -48,6 +54,9 @@ pub async fn start_async(
                 }
             }
             _ = timeout => {
+                for cmd in fades.step(Instant::now()) {
+                    send(&internal_sx, PAInternal::Command(Box::new(cmd)))?;
+                }
                 send(&internal_sx, PAInternal::Tick)?;
             }
         };
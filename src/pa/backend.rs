@@ -0,0 +1,63 @@
+use super::common::*;
+
+// Everything rsmixer needs from a sound server.
+// Backends report back by dispatching EntryUpdate, EntryRemoved, DefaultsUpdate
+// and InitialStateLoaded actions, peaks are written straight into PEAKS
+pub trait Backend {
+    // connect and send current state of every entry
+    fn connect(&mut self) -> Result<(), RSError>;
+
+    // send a fresh EntryUpdate for a single entry
+    fn request_info(&mut self, ident: EntryIdentifier) -> Result<(), RSError>;
+
+    // handle one of the PA_MESSAGE actions
+    fn handle_command(&mut self, cmd: Action) -> Result<(), RSError>;
+
+    // peak meters should run for exactly these entries
+    // (values are indexes of sources to record from)
+    fn set_monitors(
+        &mut self,
+        targets: HashMap<EntryIdentifier, MonitorTarget>,
+    ) -> Result<(), RSError>;
+
+    // called every 50ms
+    fn tick(&mut self) -> Result<(), RSError>;
+
+    // stop every peak meter and wait for the server to drop them,
+    // called once after the connection is no longer used (or was lost)
+    fn disconnect(&mut self) -> Result<(), RSError>;
+}
+
+pub fn run<B: Backend>(
+    backend: &mut B,
+    internal_rx: cb_channel::Receiver<PAInternal>,
+) -> Result<(), RSError> {
+    backend.connect()?;
+
+    let result = handle_messages(backend, internal_rx);
+    let disconnected = backend.disconnect();
+
+    result.and(disconnected)
+}
+
+// returns when rsmixer exits (the channel gets closed) or PulseAudio disconnects
+fn handle_messages<B: Backend>(
+    backend: &mut B,
+    internal_rx: cb_channel::Receiver<PAInternal>,
+) -> Result<(), RSError> {
+    while let Ok(msg) = internal_rx.recv() {
+        match msg {
+            PAInternal::AskInfo(ident) => backend.request_info(ident)?,
+            PAInternal::Tick => backend.tick()?,
+            PAInternal::Command(cmd) => match *cmd {
+                Action::ExitSignal | Action::PADisconnected2 => {
+                    break;
+                }
+                Action::CreateMonitors(targets) => backend.set_monitors(targets)?,
+                cmd => backend.handle_command(cmd)?,
+            },
+        };
+    }
+
+    Ok(())
+}
@@ -1,7 +1,10 @@
 use super::common::*;
 
 use crate::{
-    entry::{CardEntry, CardProfile, Entry, EntrySpaceLvl, HiddenStatus, PlayEntry},
+    entry::{
+        CardEntry, CardProfile, DevicePort, Entry, EntrySpaceLvl, HiddenStatus, PeakMeter,
+        PlayEntry,
+    },
     ui::widgets::VolumeWidget,
     DISPATCH,
 };
@@ -9,12 +12,60 @@ use crate::{
 use pulse::{
     callbacks::ListResult,
     context::{
-        introspect::{CardInfo, SinkInfo, SinkInputInfo, SourceInfo, SourceOutputInfo},
+        introspect::{CardInfo, ServerInfo, SinkInfo, SinkInputInfo, SourceInfo, SourceOutputInfo},
         subscribe::{InterestMaskSet, Operation},
     },
-    def::{SinkState, SourceState},
+    def::{PortAvailable, SinkState, SourceState},
+    proplist::{properties, Proplist},
 };
 
+// media name and application properties of a stream, used by search and rules
+fn app_properties(proplist: &Proplist, media_name: Option<&str>) -> HashMap<String, String> {
+    let mut props = HashMap::new();
+    if let Some(name) = media_name {
+        props.insert(properties::MEDIA_NAME.to_string(), name.to_string());
+    }
+    for key in &[
+        properties::APPLICATION_NAME,
+        properties::APPLICATION_PROCESS_BINARY,
+        properties::APPLICATION_ID,
+        properties::MEDIA_ROLE,
+    ] {
+        if let Some(value) = proplist.get_str(key) {
+            props.insert(key.to_string(), value);
+        }
+    }
+    props
+}
+
+// ports of a sink or a source and the index of the active one
+// (SinkInfo and SourceInfo have separate, but identical port types)
+macro_rules! device_ports {
+    ($info:expr) => {{
+        let ports: Vec<DevicePort> = $info
+            .ports
+            .iter()
+            .filter_map(|p| {
+                p.name.as_ref().map(|n| DevicePort {
+                    name: n.to_string(),
+                    description: match &p.description {
+                        Some(s) => s.to_string(),
+                        None => n.to_string(),
+                    },
+                    available: p.available != PortAvailable::No,
+                })
+            })
+            .collect();
+        let active_port = $info
+            .active_port
+            .as_ref()
+            .and_then(|a| a.name.as_ref())
+            .and_then(|n| ports.iter().position(|p| p.name == *n));
+
+        (ports, active_port)
+    }};
+}
+
 pub fn subscribe(
     context: &Rc<RefCell<Context>>,
     info_sx: mpsc::UnboundedSender<EntryIdentifier>,
@@ -34,12 +85,23 @@ pub fn subscribe(
         },
     );
 
+    // a strong reference here would keep the context alive through its own callback
+    let context_ref = Rc::downgrade(context);
     context.borrow_mut().set_subscribe_callback(Some(Box::new(
         move |facility, operation, index| {
             if let Some(facility) = facility {
                 match facility {
-                    Facility::Server | Facility::Client => {
-                        log::error!("{:?} {:?}", facility, operation);
+                    Facility::Server => {
+                        info!("[PAInterface] Server changed");
+                        if let Some(context) = context_ref.upgrade() {
+                            context
+                                .borrow()
+                                .introspect()
+                                .get_server_info(on_server_info);
+                        }
+                        return;
+                    }
+                    Facility::Client => {
                         return;
                     }
                     _ => {}
@@ -83,40 +145,17 @@ pub fn request_current_state(
 
     let introspector = context.borrow_mut().introspect();
 
-    let info_sx = info_sxx.clone();
-    introspector.get_sink_info_list(move |x: ListResult<&SinkInfo>| {
-        if let ListResult::Item(e) = x {
-            let _ = info_sx
-                .clone()
-                .send(EntryIdentifier::new(EntryType::Sink, e.index));
-        }
-    });
+    introspector.get_sink_info_list(on_sink_info(&info_sxx));
+    introspector.get_sink_input_info_list(on_sink_input_info(&info_sxx));
+    introspector.get_source_info_list(on_source_info(&info_sxx));
+    introspector.get_source_output_info_list(on_source_output_info(&info_sxx));
+    introspector.get_card_info_list(on_card_info);
 
-    let info_sx = info_sxx.clone();
-    introspector.get_sink_input_info_list(move |x: ListResult<&SinkInputInfo>| {
-        if let ListResult::Item(e) = x {
-            let _ = info_sx.send(EntryIdentifier::new(EntryType::SinkInput, e.index));
-        }
-    });
-
-    let info_sx = info_sxx.clone();
-    introspector.get_source_info_list(move |x: ListResult<&SourceInfo>| {
-        if let ListResult::Item(e) = x {
-            let _ = info_sx.send(EntryIdentifier::new(EntryType::Source, e.index));
-        }
-    });
-
-    let info_sx = info_sxx.clone();
-    introspector.get_source_output_info_list(move |x: ListResult<&SourceOutputInfo>| {
-        if let ListResult::Item(e) = x {
-            let _ = info_sx.send(EntryIdentifier::new(EntryType::SourceOutput, e.index));
-        }
-    });
-
-    introspector.get_card_info_list(move |x: ListResult<&CardInfo>| {
-        if let ListResult::Item(e) = x {
-            let _ = info_sxx.send(EntryIdentifier::new(EntryType::Card, e.index));
-        }
+    // PulseAudio answers in order, so by the time server info
+    // arrives every entry above has already been sent
+    introspector.get_server_info(|i: &ServerInfo| {
+        on_server_info(i);
+        DISPATCH.sync_event(Action::InitialStateLoaded);
     });
 
     Ok(())
@@ -150,6 +189,33 @@ pub fn request_info(
         }
     };
 }
+pub fn on_server_info(i: &ServerInfo) {
+    debug!("[PADataInterface] Update server info");
+    let default_sink = i.default_sink_name.as_ref().map(|n| n.to_string());
+    let default_source = i.default_source_name.as_ref().map(|n| n.to_string());
+
+    DISPATCH.sync_event(Action::DefaultsUpdate(default_sink, default_source));
+}
+
+// PulseAudio reports profile availability only since 13.0, before that
+// a profile is unavailable when every port used by it is unplugged
+#[cfg(not(feature = "pa_v13"))]
+fn profile_ports_available(
+    ports: &[pulse::context::introspect::CardPortInfo],
+    profile: &str,
+) -> bool {
+    let used = ports
+        .iter()
+        .filter(|port| {
+            port.profiles
+                .iter()
+                .any(|p| p.name.as_deref() == Some(profile))
+        })
+        .collect::<Vec<_>>();
+
+    used.is_empty() || used.iter().any(|p| p.available != PortAvailable::No)
+}
+
 pub fn on_card_info(res: ListResult<&CardInfo>) {
     if let ListResult::Item(i) = res {
         let n = match i
@@ -172,6 +238,11 @@ pub fn on_card_info(res: ListResult<&CardInfo>) {
                         },
                         #[cfg(any(feature = "pa_v13"))]
                         available: p.available,
+                        #[cfg(not(feature = "pa_v13"))]
+                        available: profile_ports_available(&i.ports, n),
+                        sinks: p.n_sinks,
+                        sources: p.n_sources,
+                        priority: p.priority,
                     })
                 } else {
                     None
@@ -199,6 +270,8 @@ pub fn on_card_info(res: ListResult<&CardInfo>) {
             parent: None,
             position: EntrySpaceLvl::Empty,
             is_selected: false,
+            is_default: false,
+            is_match: false,
             card_entry: Some(CardEntry {
                 profiles,
                 selected_profile,
@@ -221,6 +294,7 @@ pub fn on_sink_info(
                 None => String::new(),
             };
             let ident = EntryIdentifier::new(EntryType::Sink, i.index);
+            let (ports, active_port) = device_ports!(i);
             let entry = Entry {
                 entry_type: EntryType::Sink,
                 hidden: HiddenStatus::Show,
@@ -229,16 +303,26 @@ pub fn on_sink_info(
                 parent: None,
                 position: EntrySpaceLvl::Empty,
                 is_selected: false,
+                is_default: false,
+                is_match: false,
                 card_entry: None,
                 play_entry: Some(PlayEntry {
                     volume_bar: VolumeWidget::default(),
-                    peak_volume_bar: VolumeWidget::default(),
-                    peak: 0.0,
+                    channel_meters: Vec::new(),
+                    meter: PeakMeter::default(),
                     mute: i.mute,
                     volume: i.volume,
+                    channel_map: i.channel_map,
                     monitor_source: Some(i.monitor_source),
                     sink: None,
+                    device_name: i.name.as_ref().map(|n| n.to_string()),
+                    properties: HashMap::new(),
                     suspended: i.state == SinkState::Suspended,
+                    corked: false,
+                    expanded: false,
+                    ports,
+                    active_port,
+                    selected_channel: None,
                 }),
             };
             DISPATCH.sync_event(Action::EntryUpdate(ident, Box::new(entry)));
@@ -272,16 +356,26 @@ pub fn on_sink_input_info(
                 name: n,
                 index: i.index,
                 is_selected: false,
+                is_default: false,
+                is_match: false,
                 card_entry: None,
                 play_entry: Some(PlayEntry {
                     volume_bar: VolumeWidget::default(),
-                    peak_volume_bar: VolumeWidget::default(),
-                    peak: 0.0,
+                    channel_meters: Vec::new(),
+                    meter: PeakMeter::default(),
                     mute: i.mute,
                     volume: i.volume,
+                    channel_map: i.channel_map,
                     monitor_source: None,
                     sink: Some(i.sink),
+                    device_name: None,
+                    properties: app_properties(&i.proplist, i.name.as_deref()),
                     suspended: false,
+                    corked: i.corked,
+                    expanded: false,
+                    ports: Vec::new(),
+                    active_port: None,
+                    selected_channel: None,
                 }),
             };
             DISPATCH.sync_event(Action::EntryUpdate(ident, Box::new(entry)));
@@ -301,6 +395,7 @@ pub fn on_source_info(
                 None => String::new(),
             };
             let ident = EntryIdentifier::new(EntryType::Source, i.index);
+            let (ports, active_port) = device_ports!(i);
             let entry = Entry {
                 entry_type: EntryType::Source,
                 position: EntrySpaceLvl::Empty,
@@ -309,16 +404,26 @@ pub fn on_source_info(
                 name,
                 parent: None,
                 is_selected: false,
+                is_default: false,
+                is_match: false,
                 card_entry: None,
                 play_entry: Some(PlayEntry {
                     volume_bar: VolumeWidget::default(),
-                    peak_volume_bar: VolumeWidget::default(),
-                    peak: 0.0,
+                    channel_meters: Vec::new(),
+                    meter: PeakMeter::default(),
                     mute: i.mute,
                     volume: i.volume,
+                    channel_map: i.channel_map,
                     monitor_source: Some(i.index),
                     sink: None,
+                    device_name: i.name.as_ref().map(|n| n.to_string()),
+                    properties: HashMap::new(),
                     suspended: i.state == SourceState::Suspended,
+                    corked: false,
+                    expanded: false,
+                    ports,
+                    active_port,
+                    selected_channel: None,
                 }),
             };
             DISPATCH.sync_event(Action::EntryUpdate(ident, Box::new(entry)));
@@ -352,16 +457,26 @@ pub fn on_source_output_info(
                 name: n,
                 position: EntrySpaceLvl::Empty,
                 is_selected: false,
+                is_default: false,
+                is_match: false,
                 card_entry: None,
                 play_entry: Some(PlayEntry {
                     volume_bar: VolumeWidget::default(),
-                    peak_volume_bar: VolumeWidget::default(),
-                    peak: 0.0,
+                    channel_meters: Vec::new(),
+                    meter: PeakMeter::default(),
                     mute: i.mute,
                     volume: i.volume,
+                    channel_map: i.channel_map,
                     monitor_source: Some(i.source),
                     sink: None,
+                    device_name: None,
+                    properties: app_properties(&i.proplist, i.name.as_deref()),
                     suspended: false,
+                    corked: i.corked,
+                    expanded: false,
+                    ports: Vec::new(),
+                    active_port: None,
+                    selected_channel: None,
                 }),
             };
             DISPATCH.sync_event(Action::EntryUpdate(ident, Box::new(entry)));
@@ -2,6 +2,7 @@ pub use super::{monitor::Monitors, PAInternal, SPEC};
 
 pub use crate::{
     entry::{EntryIdentifier, EntryType},
+    models::MonitorTarget,
     Action, RSError, DISPATCH,
 };
 
@@ -18,7 +19,7 @@ pub use pulse::{
     },
 };
 
-pub use log::{debug, error, info, warn};
+pub use log::{debug, error, info};
 
 impl From<Facility> for EntryType {
     fn from(fac: Facility) -> Self {
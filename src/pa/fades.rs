@@ -0,0 +1,71 @@
+use super::common::*;
+
+use crate::models::Fade;
+
+use std::time::Instant;
+
+// fades in progress, every tick of the loop moves them a step further
+#[derive(Default)]
+pub struct Fades(HashMap<EntryIdentifier, (Instant, Fade)>);
+
+impl Fades {
+    // keeps track of commands that start or interrupt a fade,
+    // returns true if the command shouldn't go to PulseAudio
+    pub fn handle(&mut self, cmd: &Action) -> bool {
+        match cmd {
+            Action::Fade(fade) => {
+                self.start(fade.clone(), Instant::now());
+                return true;
+            }
+            // the entry was changed some other way, that stays
+            Action::SetVolume(ident, _) | Action::KillEntry(ident) => {
+                self.0.remove(ident);
+            }
+            Action::PADisconnected2 => {
+                self.0.clear();
+            }
+            _ => {}
+        };
+        false
+    }
+
+    pub fn start(&mut self, mut fade: Fade, now: Instant) {
+        // muting again before a soft mute ended has to bring back the volume from before it
+        if let Some((_, old)) = self.0.get(&fade.ident) {
+            if old.mute && fade.mute {
+                fade.from = old.from;
+            }
+        }
+        self.0.insert(fade.ident, (now, fade));
+    }
+
+    // commands for PulseAudio bringing every fade to where it should be at `now`
+    pub fn step(&mut self, now: Instant) -> Vec<Action> {
+        let mut actions = Vec::new();
+
+        self.0.retain(|ident, (start, fade)| {
+            let elapsed = now.saturating_duration_since(*start);
+            actions.push(Action::SetVolume(*ident, fade.volume_at(elapsed)));
+
+            if elapsed < fade.duration {
+                return true;
+            }
+            actions.extend(fade.finish());
+            false
+        });
+
+        actions
+    }
+
+    // commands bringing every fade to its end at once, nothing moves them after exiting
+    pub fn finish(&mut self) -> Vec<Action> {
+        let mut actions = Vec::new();
+
+        for (ident, (_, fade)) in self.0.drain() {
+            actions.push(Action::SetVolume(ident, fade.to));
+            actions.extend(fade.finish());
+        }
+
+        actions
+    }
+}
@@ -0,0 +1,427 @@
+use super::{backend::Backend, common::*};
+
+use crate::{
+    entry::{
+        percent_to_volume, CardEntry, CardProfile, DevicePort, Entries, Entry, EntrySpaceLvl,
+        HiddenStatus, PeakMeter, PlayEntry,
+    },
+    ui::widgets::VolumeWidget,
+    PEAKS,
+};
+
+use std::collections::HashSet;
+
+use pulse::{
+    channelmap::{Map, Position},
+    volume::ChannelVolumes,
+};
+
+// In-memory sound server for tests. Instead of dispatching them,
+// every action it would send is kept in `events` until taken.
+// Peaks are written into PEAKS, like monitors do
+#[derive(Default)]
+pub struct FakeBackend {
+    entries: Entries,
+    default_sink: Option<String>,
+    default_source: Option<String>,
+    monitors: HashMap<EntryIdentifier, MonitorTarget>,
+    // entries with peaks in PEAKS
+    peaks: HashSet<EntryIdentifier>,
+    events: Vec<Action>,
+    // every command handled so far
+    pub commands: Vec<Action>,
+    pub disconnected: bool,
+}
+
+impl FakeBackend {
+    pub fn sink(self, index: u32, name: &str, percent: i16) -> Self {
+        self.play_entry(EntryType::Sink, index, name, None, percent)
+    }
+
+    pub fn sink_input(self, index: u32, name: &str, sink: u32, percent: i16) -> Self {
+        self.play_entry(EntryType::SinkInput, index, name, Some(sink), percent)
+    }
+
+    pub fn source(self, index: u32, name: &str, percent: i16) -> Self {
+        self.play_entry(EntryType::Source, index, name, None, percent)
+    }
+
+    pub fn source_output(self, index: u32, name: &str, source: u32, percent: i16) -> Self {
+        self.play_entry(EntryType::SourceOutput, index, name, Some(source), percent)
+    }
+
+    pub fn card(mut self, index: u32, name: &str, profiles: &[&str], active: usize) -> Self {
+        let profiles = profiles
+            .iter()
+            .map(|p| CardProfile {
+                name: p.to_string(),
+                description: p.to_string(),
+                available: true,
+                sinks: 1,
+                sources: 0,
+                priority: 0,
+            })
+            .collect();
+
+        let mut entry = new_entry(EntryType::Card, index, name, None);
+        entry.card_entry = Some(CardEntry {
+            profiles,
+            selected_profile: Some(active),
+        });
+
+        self.entries
+            .insert(EntryIdentifier::new(EntryType::Card, index), entry);
+        self
+    }
+
+    // gives a sink/source ports with these names, the first one is active
+    pub fn ports(mut self, ident: EntryIdentifier, ports: &[&str]) -> Self {
+        if let Some(play) = self
+            .entries
+            .get_mut(&ident)
+            .and_then(|e| e.play_entry.as_mut())
+        {
+            play.ports = ports
+                .iter()
+                .map(|p| DevicePort {
+                    name: p.to_string(),
+                    description: p.to_string(),
+                    available: true,
+                })
+                .collect();
+            play.active_port = Some(0);
+        }
+        self
+    }
+
+    // proplist keys and values of a stream, like ("application.process.binary", "firefox")
+    pub fn properties(mut self, ident: EntryIdentifier, properties: &[(&str, &str)]) -> Self {
+        if let Some(play) = self
+            .entries
+            .get_mut(&ident)
+            .and_then(|e| e.play_entry.as_mut())
+        {
+            play.properties = properties
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect();
+        }
+        self
+    }
+
+    // a stream paused by its application
+    pub fn corked(mut self, ident: EntryIdentifier) -> Self {
+        if let Some(play) = self
+            .entries
+            .get_mut(&ident)
+            .and_then(|e| e.play_entry.as_mut())
+        {
+            play.corked = true;
+        }
+        self
+    }
+
+    pub fn unavailable_profile(mut self, index: u32, profile: &str) -> Self {
+        if let Some(card) = self
+            .entries
+            .get_mut(&EntryIdentifier::new(EntryType::Card, index))
+            .and_then(|e| e.card_entry.as_mut())
+        {
+            for p in card.profiles.iter_mut().filter(|p| p.name == profile) {
+                p.available = false;
+            }
+        }
+        self
+    }
+
+    pub fn default_sink(mut self, index: u32) -> Self {
+        self.default_sink = self.device_name(EntryType::Sink, index);
+        self
+    }
+
+    pub fn default_source(mut self, index: u32) -> Self {
+        self.default_source = self.device_name(EntryType::Source, index);
+        self
+    }
+
+    fn device_name(&self, entry_type: EntryType, index: u32) -> Option<String> {
+        self.entries
+            .get(&EntryIdentifier::new(entry_type, index))
+            .and_then(|e| e.play_entry.as_ref())
+            .and_then(|p| p.device_name.clone())
+    }
+
+    pub fn is_monitored(&self, ident: EntryIdentifier) -> bool {
+        self.monitors.contains_key(&ident)
+    }
+
+    pub fn peak(&mut self, ident: EntryIdentifier, peak: f32) {
+        self.channel_peaks(ident, &[peak]);
+    }
+
+    // peak meters only report entries that are monitored
+    pub fn channel_peaks(&mut self, ident: EntryIdentifier, peaks: &[f32]) {
+        if self.is_monitored(ident) {
+            PEAKS.store(ident, peaks);
+            self.peaks.insert(ident);
+        }
+    }
+
+    fn remove_peaks(&mut self) {
+        let monitors = &self.monitors;
+        self.peaks.retain(|ident| {
+            if monitors.contains_key(ident) {
+                return true;
+            }
+            PEAKS.remove(*ident);
+            false
+        });
+    }
+
+    // entries of `added` show up while connected, like a stream of an application just started
+    pub fn add(&mut self, mut added: FakeBackend) {
+        let idents = added
+            .entries
+            .iter_mut()
+            .map(|(ident, _)| *ident)
+            .collect::<Vec<_>>();
+
+        for ident in idents {
+            if let Some(entry) = added.entries.remove(&ident) {
+                self.entries.insert(ident, entry);
+                self.send_update(ident);
+            }
+        }
+    }
+
+    pub fn take_events(&mut self) -> Vec<Action> {
+        std::mem::take(&mut self.events)
+    }
+
+    fn play_entry(
+        mut self,
+        entry_type: EntryType,
+        index: u32,
+        name: &str,
+        parent: Option<u32>,
+        percent: i16,
+    ) -> Self {
+        let mut volume = ChannelVolumes::default();
+        volume.set_len(2);
+        for vol in volume.get_mut() {
+            *vol = percent_to_volume(percent);
+        }
+
+        let mut channel_map = Map::default();
+        channel_map.set_len(2);
+        channel_map.get_mut()[0] = Position::FrontLeft;
+        channel_map.get_mut()[1] = Position::FrontRight;
+
+        let (monitor_source, sink, device_name) = match entry_type {
+            EntryType::Sink => (
+                Some(index + 1000),
+                None,
+                Some(device_name(entry_type, name)),
+            ),
+            EntryType::Source => (Some(index), None, Some(device_name(entry_type, name))),
+            EntryType::SinkInput => (None, parent, None),
+            _ => (parent, None, None),
+        };
+
+        let mut entry = new_entry(entry_type, index, name, parent);
+        entry.play_entry = Some(PlayEntry {
+            volume_bar: VolumeWidget::default(),
+            channel_meters: Vec::new(),
+            meter: PeakMeter::default(),
+            mute: false,
+            volume,
+            channel_map,
+            monitor_source,
+            sink,
+            device_name,
+            properties: HashMap::new(),
+            suspended: false,
+            corked: false,
+            expanded: false,
+            ports: Vec::new(),
+            active_port: None,
+            selected_channel: None,
+        });
+
+        self.entries
+            .insert(EntryIdentifier::new(entry_type, index), entry);
+        self
+    }
+
+    fn send_update(&mut self, ident: EntryIdentifier) {
+        if let Some(entry) = self.entries.get(&ident) {
+            self.events
+                .push(Action::EntryUpdate(ident, Box::new(entry.clone())));
+        }
+    }
+
+    fn send_defaults(&mut self) {
+        self.events.push(Action::DefaultsUpdate(
+            self.default_sink.clone(),
+            self.default_source.clone(),
+        ));
+    }
+
+    fn change<F: FnOnce(&mut Entry)>(&mut self, ident: EntryIdentifier, f: F) {
+        if let Some(entry) = self.entries.get_mut(&ident) {
+            f(entry);
+            self.send_update(ident);
+        }
+    }
+
+    fn send_state(&mut self) {
+        let idents = [
+            EntryType::Sink,
+            EntryType::SinkInput,
+            EntryType::Source,
+            EntryType::SourceOutput,
+            EntryType::Card,
+        ]
+        .iter()
+        .flat_map(|t| self.entries.iter_type(*t))
+        .map(|(ident, _)| *ident)
+        .collect::<Vec<_>>();
+
+        for ident in idents {
+            self.send_update(ident);
+        }
+        self.send_defaults();
+        self.events.push(Action::InitialStateLoaded);
+    }
+}
+
+impl Backend for FakeBackend {
+    fn connect(&mut self) -> Result<(), RSError> {
+        self.send_state();
+
+        Ok(())
+    }
+
+    fn request_info(&mut self, ident: EntryIdentifier) -> Result<(), RSError> {
+        self.send_update(ident);
+
+        Ok(())
+    }
+
+    fn handle_command(&mut self, cmd: Action) -> Result<(), RSError> {
+        self.commands.push(cmd.clone());
+
+        match cmd {
+            Action::MuteEntry(ident, mute) => self.change(ident, |e| {
+                if let Some(play) = &mut e.play_entry {
+                    play.mute = mute;
+                }
+            }),
+            Action::SetVolume(ident, volume) => self.change(ident, |e| {
+                if let Some(play) = &mut e.play_entry {
+                    play.volume = volume;
+                }
+            }),
+            Action::SetSuspend(ident, suspend) => self.change(ident, |e| {
+                if let Some(play) = &mut e.play_entry {
+                    play.suspended = suspend;
+                }
+            }),
+            Action::SetPort(ident, port) => self.change(ident, |e| {
+                if let Some(play) = &mut e.play_entry {
+                    play.active_port = play.ports.iter().position(|p| p.name == port);
+                }
+            }),
+            Action::MoveEntryToParent(ident, parent) => self.change(ident, |e| {
+                e.parent = Some(parent.index);
+                match &mut e.play_entry {
+                    Some(play) if ident.entry_type == EntryType::SinkInput => {
+                        play.sink = Some(parent.index);
+                    }
+                    Some(play) => {
+                        play.monitor_source = Some(parent.index);
+                    }
+                    None => {}
+                }
+            }),
+            Action::ChangeCardProfile(ident, profile) => self.change(ident, |e| {
+                if let Some(card) = &mut e.card_entry {
+                    card.selected_profile = card.profiles.iter().position(|p| p.name == profile);
+                }
+            }),
+            Action::SetDefault(ident, name) => {
+                // like PulseAudio, only names of existing devices are accepted
+                let name = self
+                    .entries
+                    .iter_type(ident.entry_type)
+                    .filter_map(|(_, e)| e.play_entry.as_ref())
+                    .find(|p| p.device_name.as_ref() == Some(&name))
+                    .and(Some(name));
+
+                match ident.entry_type {
+                    EntryType::Sink => self.default_sink = name,
+                    EntryType::Source => self.default_source = name,
+                    _ => {}
+                };
+                self.send_defaults();
+            }
+            Action::KillEntry(ident) if self.entries.remove(&ident).is_some() => {
+                self.events.push(Action::EntryRemoved(ident));
+            }
+            Action::RequestSync => {
+                self.events.push(Action::Synced);
+            }
+            Action::RequestState => self.send_state(),
+            _ => {}
+        };
+
+        Ok(())
+    }
+
+    fn set_monitors(
+        &mut self,
+        targets: HashMap<EntryIdentifier, MonitorTarget>,
+    ) -> Result<(), RSError> {
+        self.monitors = targets;
+        self.remove_peaks();
+
+        Ok(())
+    }
+
+    fn tick(&mut self) -> Result<(), RSError> {
+        Ok(())
+    }
+
+    fn disconnect(&mut self) -> Result<(), RSError> {
+        self.monitors.clear();
+        self.remove_peaks();
+        self.disconnected = true;
+
+        Ok(())
+    }
+}
+
+// stays the same when entries are recreated with other indexes, like PulseAudio names do
+fn device_name(entry_type: EntryType, name: &str) -> String {
+    format!(
+        "fake_{}.{}",
+        entry_type.as_str(),
+        name.to_lowercase().replace(' ', "_")
+    )
+}
+
+fn new_entry(entry_type: EntryType, index: u32, name: &str, parent: Option<u32>) -> Entry {
+    Entry {
+        entry_type,
+        index,
+        name: name.to_string(),
+        is_selected: false,
+        is_default: false,
+        is_match: false,
+        parent,
+        position: EntrySpaceLvl::Empty,
+        play_entry: None,
+        card_entry: None,
+        hidden: HiddenStatus::Show,
+    }
+}
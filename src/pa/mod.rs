@@ -1,11 +1,18 @@
 mod async_loop;
+pub mod backend;
 mod callbacks;
 mod common;
+mod fades;
+#[cfg(test)]
+pub mod fake;
 mod monitor;
 mod pa_actions;
 mod sync_loop;
+#[cfg(test)]
+mod tests;
 
 pub use async_loop::start_async;
+pub use fades::Fades;
 pub use sync_loop::start;
 
 use common::*;
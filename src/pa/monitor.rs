@@ -1,6 +1,6 @@
 use super::common::*;
 
-use crate::DISPATCH;
+use crate::{DISPATCH, PEAKS};
 
 use std::convert::TryInto;
 
@@ -8,7 +8,33 @@ use pulse::stream::PeekResult;
 
 pub struct Monitor {
     stream: Rc<RefCell<Stream>>,
-    exit_sender: cb_channel::Sender<u32>,
+    target: MonitorTarget,
+}
+
+impl Monitor {
+    // disconnects the stream and waits until the server is done with it,
+    // otherwise it stays around as a client (mainloop has to be locked)
+    fn disconnect(&self, mainloop: &Rc<RefCell<Mainloop>>) {
+        let mut stream = self.stream.borrow_mut();
+        stream.set_read_callback(None);
+
+        if !stream.get_state().is_good() {
+            return;
+        }
+
+        let ml_ref = Rc::clone(mainloop);
+        stream.set_state_callback(Some(Box::new(move || {
+            unsafe { (*ml_ref.as_ptr()).signal(false) };
+        })));
+
+        if stream.disconnect().is_ok() {
+            while stream.get_state().is_good() {
+                mainloop.borrow_mut().wait();
+            }
+        }
+
+        stream.set_state_callback(None);
+    }
 }
 
 pub struct Monitors {
@@ -30,10 +56,10 @@ impl Monitors {
         &mut self,
         mainloop: &Rc<RefCell<Mainloop>>,
         context: &Rc<RefCell<Context>>,
-        targets: &HashMap<EntryIdentifier, Option<u32>>,
+        targets: &HashMap<EntryIdentifier, MonitorTarget>,
     ) {
         // remove failed streams
-        // then send exit signal if stream is unwanted
+        // then disconnect streams that are unwanted
         self.monitors.retain(|ident, monitor| {
             match monitor.stream.borrow_mut().get_state() {
                 pulse::stream::State::Terminated | pulse::stream::State::Failed => {
@@ -41,31 +67,44 @@ impl Monitors {
                         "[PAInterface] Disconnecting {} sink input monitor (failed state)",
                         ident.index
                     );
+                    PEAKS.remove(*ident);
                     return false;
                 }
                 _ => {}
             };
 
-            if targets.get(ident) == None {
-                let _ = monitor.exit_sender.send(0);
+            // recorded source or channels changed, the stream has to be created again
+            if targets.get(ident) != Some(&monitor.target) {
+                monitor.disconnect(mainloop);
+                PEAKS.remove(*ident);
+                return false;
             }
 
             true
         });
 
-        targets.iter().for_each(|(ident, monitor_src)| {
+        targets.iter().for_each(|(ident, target)| {
             if self.monitors.get(ident).is_none() {
-                self.create_monitor(mainloop, context, *ident, *monitor_src);
+                self.create_monitor(mainloop, context, *ident, *target);
             }
         });
     }
 
+    pub fn disconnect_all(&mut self, mainloop: &Rc<RefCell<Mainloop>>) {
+        for (ident, monitor) in self.monitors.drain() {
+            info!("[PAInterface] Disconnecting {:?} monitor", ident);
+            monitor.disconnect(mainloop);
+            PEAKS.remove(ident);
+        }
+        self.errors.clear();
+    }
+
     fn create_monitor(
         &mut self,
         mainloop: &Rc<RefCell<Mainloop>>,
         context: &Rc<RefCell<Context>>,
         ident: EntryIdentifier,
-        monitor_src: Option<u32>,
+        target: MonitorTarget,
     ) {
         if let Some(count) = self.errors.get(&ident) {
             if *count >= 5 {
@@ -76,15 +115,8 @@ impl Monitors {
         if self.monitors.contains_key(&ident) {
             return;
         }
-        let (sx, rx) = cb_channel::unbounded();
-        if let Ok(stream) = create(&mainloop, &context, &*SPEC, ident, monitor_src, rx) {
-            self.monitors.insert(
-                ident,
-                Monitor {
-                    stream,
-                    exit_sender: sx,
-                },
-            );
+        if let Ok(stream) = create(&mainloop, &context, ident, target) {
+            self.monitors.insert(ident, Monitor { stream, target });
             self.errors.remove(&ident);
         } else {
             self.error(&ident);
@@ -107,10 +139,8 @@ fn slice_to_4_bytes(slice: &[u8]) -> [u8; 4] {
 fn create(
     p_mainloop: &Rc<RefCell<Mainloop>>,
     p_context: &Rc<RefCell<Context>>,
-    p_spec: &pulse::sample::Spec,
     ident: EntryIdentifier,
-    source_index: Option<u32>,
-    close_rx: cb_channel::Receiver<u32>,
+    target: MonitorTarget,
 ) -> Result<Rc<RefCell<Stream>>, RSError> {
     info!("[PADataInterface] Attempting to create new monitor stream");
 
@@ -120,8 +150,22 @@ fn create(
         None
     };
 
+    let spec = match &target.channels {
+        Some(map) => pulse::sample::Spec {
+            channels: map.len(),
+            ..*SPEC
+        },
+        None => *SPEC,
+    };
+    let channels = spec.channels as usize;
+
     let stream = Rc::new(RefCell::new(
-        match Stream::new(&mut p_context.borrow_mut(), "RsMixer monitor", p_spec, None) {
+        match Stream::new(
+            &mut p_context.borrow_mut(),
+            "RsMixer monitor",
+            &spec,
+            target.channels.as_ref(),
+        ) {
             Some(stream) => stream,
             None => {
                 return Err(RSError::StreamCreateError);
@@ -156,7 +200,7 @@ fn create(
 
     let x;
     let mut s = None;
-    if let Some(i) = source_index {
+    if let Some(i) = target.source {
         x = i.to_string();
         s = Some(x.as_str());
     }
@@ -199,58 +243,58 @@ fn create(
 
     {
         info!("[PADataInterface] Registering stream read callback");
-        let ml_ref = Rc::clone(&p_mainloop);
         let stream_ref = Rc::downgrade(&stream);
-        stream.borrow_mut().set_read_callback(Some(Box::new(move |_size: usize| {
-            let remove_failed = || {
-                error!("[PADataInterface] Monitor failed or terminated");
-            };
-            let disconnect_stream = || {
-                warn!("[PADataInterface] {:?} Monitor existed while the sink (input)/source (output) was already gone", ident);
-                unsafe {
-                    (*(*stream_ref.as_ptr()).as_ptr()).disconnect().unwrap();
-                    (*ml_ref.as_ptr()).signal(false);
+        let slot = PEAKS.insert(ident, channels);
+        stream
+            .borrow_mut()
+            .set_read_callback(Some(Box::new(move |_size: usize| {
+                let remove_failed = || {
+                    error!("[PADataInterface] Monitor failed or terminated");
                 };
-            };
 
-            if close_rx.try_recv().is_ok() {
-                disconnect_stream();
-                return;
-            }
+                match unsafe { (*(*stream_ref.as_ptr()).as_ptr()).get_state() } {
+                    pulse::stream::State::Failed => {
+                        remove_failed();
+                    }
+                    pulse::stream::State::Terminated => {
+                        remove_failed();
+                    }
+                    pulse::stream::State::Ready => {
+                        match unsafe { (*(*stream_ref.as_ptr()).as_ptr()).peek() } {
+                            Ok(res) => match res {
+                                PeekResult::Data(data) => {
+                                    // loudest sample of the whole chunk, so short
+                                    // transients (and clipping) aren't missed.
+                                    // Samples of every channel are interleaved
+                                    let mut peaks = vec![0.0f32; channels];
+                                    for (i, c) in data.chunks_exact(4).enumerate() {
+                                        let sample = f32::from_ne_bytes(slice_to_4_bytes(c)).abs();
+                                        let peak = &mut peaks[i % channels];
+                                        *peak = peak.max(sample);
+                                    }
 
-            match unsafe {(*(*stream_ref.as_ptr()).as_ptr()).get_state() }{
-                pulse::stream::State::Failed => {
-                    remove_failed();
-                },
-                pulse::stream::State::Terminated => {
-                    remove_failed();
-                },
-                pulse::stream::State::Ready => {
-                    match unsafe{ (*(*stream_ref.as_ptr()).as_ptr()).peek() } {
-                        Ok(res) => match res {
-                            PeekResult::Data(data) => {
-                                let size = data.len();
-                                let data_slice = slice_to_4_bytes(&data[(size-4) .. size]);
-                                let peak = f32::from_ne_bytes(data_slice).abs();
-
-                                DISPATCH.sync_event(Action::PeakVolumeUpdate(ident, peak));
-
-                                unsafe { (*(*stream_ref.as_ptr()).as_ptr()).discard().unwrap(); };
-                            },
-                            PeekResult::Hole(_) => {
-                                unsafe { (*(*stream_ref.as_ptr()).as_ptr()).discard().unwrap(); };
+                                    slot.store(&peaks);
+
+                                    unsafe {
+                                        (*(*stream_ref.as_ptr()).as_ptr()).discard().unwrap();
+                                    };
+                                }
+                                PeekResult::Hole(_) => {
+                                    unsafe {
+                                        (*(*stream_ref.as_ptr()).as_ptr()).discard().unwrap();
+                                    };
+                                }
+                                _ => {}
                             },
-                            _ => {},
-                        },
-                        Err(_) => {
-                            remove_failed();
-                        },
+                            Err(_) => {
+                                remove_failed();
+                            }
+                        }
                     }
-                },
-                _ => {},
-            };
-            // unsafe {(*ml_ref.get_mut().unwrap()).signal(false)};
-        })));
+                    _ => {}
+                };
+                // unsafe {(*ml_ref.get_mut().unwrap()).signal(false)};
+            })));
     }
 
     Ok(stream)
@@ -4,7 +4,7 @@ pub fn handle_command(
     cmd: Action,
     context: &Rc<RefCell<Context>>,
     info_sx: &mpsc::UnboundedSender<EntryIdentifier>,
-) -> Option<()> {
+) {
     match cmd {
         Action::MuteEntry(ident, mute) => {
             set_mute(ident, mute, &context);
@@ -21,16 +21,27 @@ pub fn handle_command(
         Action::SetSuspend(ident, suspend) => {
             set_suspend(ident, suspend, &context);
         }
+        Action::SetPort(ident, port) => {
+            set_port(ident, port, context);
+        }
         Action::KillEntry(ident) => {
             kill_entry(ident, &context);
         }
-        Action::ExitSignal => {
-            //@TODO disconnect monitors
-            return None;
+        Action::SetDefault(ident, name) => {
+            set_default(ident, name, context);
+        }
+        Action::RequestState => {
+            let _ = super::callbacks::request_current_state(Rc::clone(context), info_sx.clone());
+        }
+        Action::RequestSync => {
+            // answer comes after every earlier command has been handled
+            context
+                .borrow_mut()
+                .introspect()
+                .get_server_info(|_| DISPATCH.sync_event(Action::Synced));
         }
         _ => {}
     };
-    Some(())
 }
 
 fn set_volume(
@@ -112,6 +123,31 @@ fn set_suspend(ident: EntryIdentifier, suspend: bool, context: &Rc<RefCell<Conte
     };
 }
 
+fn set_port(ident: EntryIdentifier, port: String, context: &Rc<RefCell<Context>>) {
+    let mut introspector = context.borrow_mut().introspect();
+    match ident.entry_type {
+        EntryType::Sink => {
+            introspector.set_sink_port_by_index(ident.index, &port[..], None);
+        }
+        EntryType::Source => {
+            introspector.set_source_port_by_index(ident.index, &port[..], None);
+        }
+        _ => {}
+    };
+}
+
+fn set_default(ident: EntryIdentifier, name: String, context: &Rc<RefCell<Context>>) {
+    match ident.entry_type {
+        EntryType::Sink => {
+            context.borrow_mut().set_default_sink(&name, |_| {});
+        }
+        EntryType::Source => {
+            context.borrow_mut().set_default_source(&name, |_| {});
+        }
+        _ => {}
+    };
+}
+
 fn kill_entry(ident: EntryIdentifier, context: &Rc<RefCell<Context>>) {
     let mut introspector = context.borrow_mut().introspect();
     match ident.entry_type {
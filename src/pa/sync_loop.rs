@@ -1,4 +1,9 @@
-use super::{callbacks, common::*, pa_actions};
+use super::{
+    backend::{self, Backend},
+    callbacks,
+    common::*,
+    pa_actions,
+};
 
 use std::ops::Deref;
 
@@ -8,162 +13,214 @@ pub fn start(
     internal_rx: cb_channel::Receiver<PAInternal>,
     info_sx: mpsc::UnboundedSender<EntryIdentifier>,
 ) -> Result<(), RSError> {
-    // Create new mainloop and context
-    let mut proplist = Proplist::new().unwrap();
-    proplist
-        .set_str(pulse::proplist::properties::APPLICATION_NAME, "RsMixer")
-        .unwrap();
-
-    debug!("[PAInterface] Creating new mainloop");
-    let mainloop = Rc::new(RefCell::new(match Mainloop::new() {
-        Some(ml) => ml,
-        None => {
-            error!("[PAInterface] Error while creating new mainloop");
-            return Err(RSError::MainloopCreateError);
-        }
-    }));
-
-    debug!("[PAInterface] Creating new context");
-    let context = Rc::new(RefCell::new(
-        match Context::new_with_proplist(
-            mainloop.borrow_mut().deref().deref(),
-            "RsMixerContext",
-            &proplist,
-        ) {
-            Some(ctx) => ctx,
+    backend::run(&mut PulseAudioBackend::new(info_sx)?, internal_rx)
+}
+
+pub struct PulseAudioBackend {
+    mainloop: Rc<RefCell<Mainloop>>,
+    context: Rc<RefCell<Context>>,
+    info_sx: mpsc::UnboundedSender<EntryIdentifier>,
+    monitors: Monitors,
+    last_targets: HashMap<EntryIdentifier, MonitorTarget>,
+}
+
+impl PulseAudioBackend {
+    pub fn new(info_sx: mpsc::UnboundedSender<EntryIdentifier>) -> Result<Self, RSError> {
+        // Create new mainloop and context
+        let mut proplist = Proplist::new().unwrap();
+        proplist
+            .set_str(pulse::proplist::properties::APPLICATION_NAME, "RsMixer")
+            .unwrap();
+
+        debug!("[PAInterface] Creating new mainloop");
+        let mainloop = Rc::new(RefCell::new(match Mainloop::new() {
+            Some(ml) => ml,
             None => {
-                error!("[PAInterface] Error while creating new context");
+                error!("[PAInterface] Error while creating new mainloop");
                 return Err(RSError::MainloopCreateError);
             }
-        },
-    ));
-
-    // Context state change callback
-    {
-        debug!("[PAInterface] Registering state change callback");
-        let ml_ref = Rc::clone(&mainloop);
-        let context_ref = Rc::clone(&context);
-        context
-            .borrow_mut()
-            .set_state_callback(Some(Box::new(move || {
-                let state = unsafe { (*context_ref.as_ptr()).get_state() };
-                match state {
-                    pulse::context::State::Ready
-                    | pulse::context::State::Failed
-                    | pulse::context::State::Terminated => {
-                        unsafe { (*ml_ref.as_ptr()).signal(false) };
-                    }
-                    _ => {}
+        }));
+
+        debug!("[PAInterface] Creating new context");
+        let context = Rc::new(RefCell::new(
+            match Context::new_with_proplist(
+                mainloop.borrow_mut().deref().deref(),
+                "RsMixerContext",
+                &proplist,
+            ) {
+                Some(ctx) => ctx,
+                None => {
+                    error!("[PAInterface] Error while creating new context");
+                    return Err(RSError::MainloopCreateError);
                 }
-            })));
+            },
+        ));
+
+        Ok(Self {
+            mainloop,
+            context,
+            info_sx,
+            monitors: Monitors::default(),
+            last_targets: HashMap::new(),
+        })
     }
 
-    // Try to connect to pulseaudio
-    debug!("[PAInterface] Connecting context");
-
-    match context
-        .borrow_mut()
-        .connect(None, pulse::context::FlagSet::NOFLAGS, None)
-    {
-        Ok(_) => {}
-        Err(_) => {
-            error!("[PAInterface] Error while connecting context");
-            return Err(RSError::MainloopConnectError);
+    // runs f with the mainloop locked, fails if the connection was lost
+    fn locked<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> Result<T, RSError> {
+        self.mainloop.borrow_mut().lock();
+
+        match self.context.borrow_mut().get_state() {
+            pulse::context::State::Ready => {}
+            _ => {
+                self.mainloop.borrow_mut().unlock();
+                return Err(RSError::PulseAudioDisconnected);
+            }
         }
-    };
 
-    info!("[PAInterface] Starting mainloop");
+        let result = f(self);
 
-    // start mainloop
-    mainloop.borrow_mut().lock();
-    match mainloop.borrow_mut().start() {
-        Ok(_) => {}
-        Err(_) => {
-            return Err(RSError::MainloopConnectError);
-        }
+        self.mainloop.borrow_mut().unlock();
+
+        Ok(result)
     }
+}
 
-    debug!("[PAInterface] Waiting for context to be ready...");
-    // wait for context to be ready
-    loop {
-        match context.borrow_mut().get_state() {
-            pulse::context::State::Ready => {
-                break;
-            }
-            pulse::context::State::Failed | pulse::context::State::Terminated => {
-                mainloop.borrow_mut().unlock();
-                mainloop.borrow_mut().stop();
-                error!("[PAInterface] Connection failed or context terminated");
+impl Backend for PulseAudioBackend {
+    fn connect(&mut self) -> Result<(), RSError> {
+        let mainloop = &self.mainloop;
+        let context = &self.context;
+
+        // Context state change callback
+        {
+            debug!("[PAInterface] Registering state change callback");
+            let ml_ref = Rc::clone(mainloop);
+            let context_ref = Rc::clone(context);
+            context
+                .borrow_mut()
+                .set_state_callback(Some(Box::new(move || {
+                    let state = unsafe { (*context_ref.as_ptr()).get_state() };
+                    match state {
+                        pulse::context::State::Ready
+                        | pulse::context::State::Failed
+                        | pulse::context::State::Terminated => {
+                            unsafe { (*ml_ref.as_ptr()).signal(false) };
+                        }
+                        _ => {}
+                    }
+                })));
+        }
+
+        // Try to connect to pulseaudio
+        debug!("[PAInterface] Connecting context");
+
+        match context
+            .borrow_mut()
+            .connect(None, pulse::context::FlagSet::NOFLAGS, None)
+        {
+            Ok(_) => {}
+            Err(_) => {
+                error!("[PAInterface] Error while connecting context");
                 return Err(RSError::MainloopConnectError);
             }
-            _ => {
-                mainloop.borrow_mut().wait();
-            }
-        }
-    }
-    debug!("[PAInterface] Context ready");
-
-    context.borrow_mut().set_state_callback(None);
-    // {
-    //     debug!("[PAInterface] Registering state change callback");
-    //     let ml_ref = Rc::clone(&mainloop);
-    //     let context_ref = Rc::clone(&context);
-    //     context
-    //         .borrow_mut()
-    //         .set_state_callback(Some(Box::new(move || {
-    //             let state = unsafe { (*context_ref.as_ptr()).get_state() };
-    //             match state {
-    //                 pulse::context::State::Failed
-    //                 | pulse::context::State::Terminated => {
-    //                 }
-    //                 _ => {}
-    //             }
-    //         })));
-    // }
-
-    callbacks::subscribe(&context, info_sx.clone())?;
-    callbacks::request_current_state(Rc::clone(&context), info_sx.clone())?;
-
-    mainloop.borrow_mut().unlock();
-
-    debug!("[PAInterface] Actually starting our mainloop");
-
-    let mut monitors = Monitors::default();
-    let mut last_targets = HashMap::new();
-
-    while let Ok(msg) = internal_rx.recv() {
-        mainloop.borrow_mut().lock();
+        };
 
-        match context.borrow_mut().get_state() {
-            pulse::context::State::Ready => {}
-            _ => {
-                mainloop.borrow_mut().unlock();
-                return Err(RSError::PulseAudioDisconnected);
+        info!("[PAInterface] Starting mainloop");
+
+        // start mainloop
+        mainloop.borrow_mut().lock();
+        match mainloop.borrow_mut().start() {
+            Ok(_) => {}
+            Err(_) => {
+                return Err(RSError::MainloopConnectError);
             }
         }
 
-        match msg {
-            PAInternal::AskInfo(ident) => {
-                callbacks::request_info(ident, &context, info_sx.clone());
-            }
-            PAInternal::Tick => {
-                // remove failed monitors
-                monitors.filter(&mainloop, &context, &last_targets);
-            }
-            PAInternal::Command(cmd) => {
-                let cmd = cmd.deref();
-                if pa_actions::handle_command(cmd.clone(), &context, &info_sx).is_none() {
+        debug!("[PAInterface] Waiting for context to be ready...");
+        // wait for context to be ready
+        loop {
+            match context.borrow_mut().get_state() {
+                pulse::context::State::Ready => {
                     break;
                 }
-
-                if let Action::CreateMonitors(mons) = cmd.clone() {
-                    last_targets = mons;
-                    monitors.filter(&mainloop, &context, &last_targets);
+                pulse::context::State::Failed | pulse::context::State::Terminated => {
+                    mainloop.borrow_mut().unlock();
+                    mainloop.borrow_mut().stop();
+                    error!("[PAInterface] Connection failed or context terminated");
+                    return Err(RSError::MainloopConnectError);
+                }
+                _ => {
+                    mainloop.borrow_mut().wait();
                 }
             }
-        };
+        }
+        debug!("[PAInterface] Context ready");
+
+        context.borrow_mut().set_state_callback(None);
+        // {
+        //     debug!("[PAInterface] Registering state change callback");
+        //     let ml_ref = Rc::clone(&mainloop);
+        //     let context_ref = Rc::clone(&context);
+        //     context
+        //         .borrow_mut()
+        //         .set_state_callback(Some(Box::new(move || {
+        //             let state = unsafe { (*context_ref.as_ptr()).get_state() };
+        //             match state {
+        //                 pulse::context::State::Failed
+        //                 | pulse::context::State::Terminated => {
+        //                 }
+        //                 _ => {}
+        //             }
+        //         })));
+        // }
+
+        callbacks::subscribe(context, self.info_sx.clone())?;
+        callbacks::request_current_state(Rc::clone(context), self.info_sx.clone())?;
+
         mainloop.borrow_mut().unlock();
+
+        debug!("[PAInterface] Actually starting our mainloop");
+
+        Ok(())
+    }
+
+    fn request_info(&mut self, ident: EntryIdentifier) -> Result<(), RSError> {
+        self.locked(|pa| callbacks::request_info(ident, &pa.context, pa.info_sx.clone()))
+    }
+
+    fn handle_command(&mut self, cmd: Action) -> Result<(), RSError> {
+        self.locked(|pa| pa_actions::handle_command(cmd, &pa.context, &pa.info_sx))
+    }
+
+    fn set_monitors(
+        &mut self,
+        targets: HashMap<EntryIdentifier, MonitorTarget>,
+    ) -> Result<(), RSError> {
+        self.locked(|pa| {
+            pa.last_targets = targets;
+            pa.monitors
+                .filter(&pa.mainloop, &pa.context, &pa.last_targets);
+        })
     }
 
-    Ok(())
+    fn tick(&mut self) -> Result<(), RSError> {
+        // remove failed monitors
+        self.locked(|pa| {
+            pa.monitors
+                .filter(&pa.mainloop, &pa.context, &pa.last_targets)
+        })
+    }
+
+    fn disconnect(&mut self) -> Result<(), RSError> {
+        info!("[PAInterface] Disconnecting");
+
+        self.mainloop.borrow_mut().lock();
+        self.monitors.disconnect_all(&self.mainloop);
+        self.context.borrow_mut().set_subscribe_callback(None);
+        self.context.borrow_mut().disconnect();
+        self.mainloop.borrow_mut().unlock();
+
+        self.mainloop.borrow_mut().stop();
+
+        Ok(())
+    }
 }
@@ -0,0 +1,106 @@
+use super::{backend, fake::FakeBackend, Fades, PAInternal};
+
+use crate::{
+    entry::{percent_to_volume, EntryIdentifier, EntryType},
+    models::{Fade, FadeCurve, MonitorTarget},
+    Action,
+};
+
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use pulse::volume::{ChannelVolumes, Volume, VolumeDB};
+
+fn sink() -> EntryIdentifier {
+    EntryIdentifier::new(EntryType::Sink, 0)
+}
+
+fn command(action: Action) -> PAInternal {
+    PAInternal::Command(Box::new(action))
+}
+
+fn monitor_sink(sx: &cb_channel::Sender<PAInternal>) {
+    let mut targets = HashMap::new();
+    targets.insert(
+        sink(),
+        MonitorTarget {
+            source: Some(1000),
+            channels: None,
+        },
+    );
+    sx.send(command(Action::CreateMonitors(targets))).unwrap();
+}
+
+#[test]
+fn monitors_are_disconnected_on_exit() {
+    let mut fake = FakeBackend::default().sink(0, "Speakers", 50);
+    let (sx, rx) = cb_channel::unbounded();
+
+    monitor_sink(&sx);
+    // the async loop stops forwarding messages when rsmixer exits
+    drop(sx);
+
+    backend::run(&mut fake, rx).unwrap();
+
+    assert!(fake.disconnected);
+    assert!(!fake.is_monitored(sink()));
+}
+
+#[test]
+fn monitors_are_drained_on_pa_disconnect() {
+    let mut fake = FakeBackend::default().sink(0, "Speakers", 50);
+    let (sx, rx) = cb_channel::unbounded();
+
+    monitor_sink(&sx);
+    sx.send(command(Action::PADisconnected2)).unwrap();
+    sx.send(command(Action::MuteEntry(sink(), true))).unwrap();
+
+    backend::run(&mut fake, rx).unwrap();
+
+    assert!(fake.disconnected);
+    assert!(!fake.is_monitored(sink()));
+    assert!(fake.commands.is_empty());
+}
+
+#[test]
+fn soft_mute_fades_out_then_mutes_and_brings_volume_back() {
+    let mut from = ChannelVolumes::default();
+    from.set_len(2);
+    for vol in from.get_mut() {
+        *vol = percent_to_volume(100);
+    }
+    let mut to = from;
+    to.scale(Volume::MUTED);
+
+    let mut fades = Fades::default();
+    let start = Instant::now();
+    fades.start(
+        Fade {
+            ident: sink(),
+            from,
+            to,
+            duration: Duration::from_millis(300),
+            curve: FadeCurve::Log,
+            mute: true,
+        },
+        start,
+    );
+
+    // halfway in decibels between 0dB and -60dB
+    let step = fades.step(start + Duration::from_millis(150));
+    match step.as_slice() {
+        [Action::SetVolume(_, vols)] => {
+            assert_eq!(VolumeDB::from(vols.max()).0.round(), -30.0)
+        }
+        other => panic!("unexpected {:?}", other),
+    }
+
+    let end = fades.step(start + Duration::from_millis(300));
+    assert_eq!(end.len(), 3);
+    assert!(matches!(&end[0], Action::SetVolume(_, v) if v.get() == to.get()));
+    assert_eq!(end[1], Action::MuteEntry(sink(), true));
+    assert!(matches!(&end[2], Action::SetVolume(_, v) if v.get() == from.get()));
+    assert!(fades.step(start + Duration::from_secs(1)).is_empty());
+}
@@ -1,6 +1,6 @@
 use crate::{
-    event_loop::event_loop, input, models::actions::statics::*, pa, ui, Action, RSError, DISPATCH,
-    SENDERS, VARIABLES,
+    event_loop::event_loop, input, models::actions::statics::*, pa, socket, ui, Action, RSError,
+    DISPATCH, SENDERS, VARIABLES,
 };
 
 use ev_apple::EventsManager;
@@ -20,13 +20,15 @@ pub async fn run() -> Result<(), RSError> {
 
     let event_loop = run_event_loop().await;
     let input_loop = run_input_loop();
+    let socket_loop = run_socket_loop();
     let pa = run_pa().await;
 
     let pa = task::spawn(pa);
     let input_loop = task::spawn(input_loop);
+    let socket_loop = task::spawn(socket_loop);
     let event_loop = task::spawn(event_loop);
 
-    let r = tokio::try_join!(input_loop, pa, event_loop);
+    let r = tokio::try_join!(input_loop, pa, socket_loop, event_loop);
 
     DISPATCH.event(Action::ExitSignal).await;
 
@@ -38,7 +40,7 @@ pub async fn run() -> Result<(), RSError> {
     }
 }
 
-async fn run_events() -> impl Future<Output = Result<(), RSError>> {
+pub async fn run_events() -> impl Future<Output = Result<(), RSError>> {
     let ev_manager = EventsManager::prepare(&DISPATCH, EXIT_MESSAGE_ID).await;
 
     async move {
@@ -70,7 +72,18 @@ async fn run_input_loop() -> Result<(), RSError> {
         Err(e) => Err(RSError::TaskHandleError(e)),
     }
 }
-async fn run_pa() -> impl Future<Output = Result<(), RSError>> {
+
+async fn run_socket_loop() -> Result<(), RSError> {
+    let (sx, rx) = channel(CHANNEL_CAPACITY);
+    SENDERS.register(SOCKET_MESSAGE, sx).await;
+
+    match task::spawn(socket::start(rx)).await {
+        Ok(r) => r,
+        Err(e) => Err(RSError::TaskHandleError(e)),
+    }
+}
+
+pub async fn run_pa() -> impl Future<Output = Result<(), RSError>> {
     async move { run_pa_internal().await }
 }
 
@@ -84,16 +97,21 @@ async fn run_pa_internal() -> Result<(), RSError> {
         let (pa_sx, pa_rx) = cb_channel::unbounded();
         let (info_sx, info_rx) = mpsc::unbounded_channel();
 
-        let async_pa = task::spawn(async move { pa::start_async(pa_sx.clone(), info_rx).await });
-        let sync_pa = task::spawn_blocking(move || pa::start(pa_rx, info_sx));
+        let mut async_pa =
+            task::spawn(async move { pa::start_async(pa_sx.clone(), info_rx).await });
+        let mut sync_pa = task::spawn_blocking(move || pa::start(pa_rx, info_sx));
         DISPATCH.event(Action::ConnectToPA).await;
 
         let result = tokio::select! {
-            res = async_pa => match res {
-                Ok(r) => r,
-                Err(e) => { return Err(RSError::TaskHandleError(e)); },
+            res = &mut async_pa => {
+                // the sync loop stops right after, let it disconnect monitors first
+                let _ = (&mut sync_pa).await;
+                match res {
+                    Ok(r) => r,
+                    Err(e) => { return Err(RSError::TaskHandleError(e)); },
+                }
             },
-            res = sync_pa => {
+            res = &mut sync_pa => {
                 match res {
                     Ok(r) => r,
                     Err(e) => { return Err(RSError::TaskHandleError(e)); },
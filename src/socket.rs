@@ -0,0 +1,127 @@
+use crate::{Action, RSError, DISPATCH};
+
+use std::{
+    collections::HashMap,
+    env,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::{UnixListener, UnixStream},
+    stream::StreamExt,
+    sync::{broadcast::Receiver, oneshot},
+    task,
+};
+
+static REPLY_TIMEOUT: Duration = Duration::from_secs(2);
+
+static NEXT_REQUEST_ID: AtomicU64 = AtomicU64::new(0);
+
+type PendingReplies = Arc<Mutex<HashMap<u64, oneshot::Sender<String>>>>;
+
+pub fn socket_path() -> Option<PathBuf> {
+    env::var_os("XDG_RUNTIME_DIR").map(|dir| PathBuf::from(dir).join("rsmixer.sock"))
+}
+
+// the socket is optional, so problems with it are only logged and never close rsmixer
+pub async fn start(mut rx: Receiver<Action>) -> Result<(), RSError> {
+    let path = match socket_path() {
+        Some(path) => path,
+        None => {
+            log::warn!("XDG_RUNTIME_DIR is not set, control socket won't be created");
+            return Ok(());
+        }
+    };
+
+    if path.exists() {
+        if UnixStream::connect(&path).await.is_ok() {
+            log::warn!("{:?} is used by another rsmixer instance", path);
+            return Ok(());
+        }
+
+        // left behind by rsmixer that didn't exit cleanly
+        if let Err(err) = std::fs::remove_file(&path) {
+            log::warn!("Couldn't remove old control socket {:?}: {}", path, err);
+            return Ok(());
+        }
+    }
+
+    let mut listener = match UnixListener::bind(&path) {
+        Ok(listener) => listener,
+        Err(err) => {
+            log::warn!("Couldn't create control socket {:?}: {}", path, err);
+            return Ok(());
+        }
+    };
+
+    let pending: PendingReplies = Arc::new(Mutex::new(HashMap::new()));
+
+    loop {
+        tokio::select! {
+            conn = listener.accept() => {
+                if let Ok((stream, _)) = conn {
+                    task::spawn(handle_client(stream, Arc::clone(&pending)));
+                }
+            }
+            ev = rx.next() => {
+                match ev {
+                    Some(Ok(Action::SocketReply(id, reply))) => {
+                        if let Some(sx) = pending.lock().unwrap().remove(&id) {
+                            let _ = sx.send(reply);
+                        }
+                    }
+                    Some(Ok(Action::ExitSignal)) | None => {
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+        };
+    }
+
+    if let Err(err) = std::fs::remove_file(&path) {
+        log::warn!("Couldn't remove control socket {:?}: {}", path, err);
+    }
+
+    Ok(())
+}
+
+// every line is a single request and gets a single line in reply
+async fn handle_client(mut stream: UnixStream, pending: PendingReplies) {
+    let (reader, mut writer) = stream.split();
+    let mut lines = BufReader::new(reader).lines();
+
+    while let Ok(Some(line)) = lines.next_line().await {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let id = NEXT_REQUEST_ID.fetch_add(1, Ordering::Relaxed);
+        let (sx, rx) = oneshot::channel();
+        pending.lock().unwrap().insert(id, sx);
+
+        DISPATCH.event(Action::SocketRequest(id, line)).await;
+
+        let reply = match tokio::time::timeout(REPLY_TIMEOUT, rx).await {
+            Ok(Ok(reply)) => reply,
+            _ => {
+                pending.lock().unwrap().remove(&id);
+                "error: no reply from rsmixer".to_string()
+            }
+        };
+
+        if writer
+            .write_all(format!("{}\n", reply).as_bytes())
+            .await
+            .is_err()
+        {
+            break;
+        }
+    }
+}
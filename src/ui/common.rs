@@ -4,7 +4,7 @@ pub use crate::{
     draw_rect,
     entry::{Entry, EntryType},
     models::RSState,
-    ui::util::{entry_height, get_style, Rect, Y_PADDING},
+    ui::util::{get_style, terminal_size, Rect, Y_PADDING},
     RSError,
 };
 
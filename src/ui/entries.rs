@@ -1,6 +1,8 @@
-use super::common::*;
+use super::{common::*, scenes::draw_scenes, widgets::VolumeInputWidget};
 
-use std::collections::HashSet;
+use crate::models::{PageType, UIMode};
+
+use std::{cmp::min, collections::HashSet};
 
 pub async fn draw_entries<W: Write>(
     stdout: &mut W,
@@ -8,6 +10,10 @@ pub async fn draw_entries<W: Write>(
     area: Rect,
     affected: Option<HashSet<usize>>,
 ) -> Result<(), RSError> {
+    if state.current_page == PageType::Scenes {
+        return draw_scenes(stdout, state, area, affected).await;
+    }
+
     let mut entry_size = area.h(3);
 
     if affected.is_none() {
@@ -15,24 +21,50 @@ pub async fn draw_entries<W: Write>(
     }
 
     for (i, lvl) in state.page_entries.visible_range_with_lvl(state.scroll) {
+        let height = state.page_entries.height(i);
+        entry_size.height = height;
+
         if let Some(aff) = affected.clone() {
             if aff.get(&i).is_none() {
-                entry_size.y += entry_height(lvl);
+                entry_size.y += height;
                 continue;
             }
         }
 
-        let ent = match state.entries.get_mut(&state.page_entries.get(i).unwrap()) {
-            Some(x) => x,
+        let ident = state.page_entries.get(i).unwrap();
+        let is_default = match state.entries.get(&ident) {
+            Some(x) => state.is_default(x),
             None => {
                 continue;
             }
         };
+
+        let ent = state.entries.get_mut(&ident).unwrap();
         ent.position = lvl;
+        ent.is_match = !state.search.is_empty() && ent.matches(&state.search);
         ent.is_selected = state.selected == i;
+        ent.is_default = is_default;
+        if let Some(play) = ent.play_entry.as_mut() {
+            play.expanded = state.expanded.contains(&ident);
+            play.selected_channel = if ent.is_selected {
+                state.selected_channel
+            } else {
+                None
+            };
+        }
 
         ent.render(entry_size, stdout)?;
-        entry_size.y += entry_height(lvl);
+
+        if ent.is_selected && state.ui_mode == UIMode::InputVolumeValue {
+            let mut area = Entry::calc_area(lvl, entry_size);
+            area.y += 1;
+            area.height = 1;
+            area.width = min(area.width, 35);
+
+            VolumeInputWidget::new(state.input_exact_volume.clone()).render(area, stdout)?;
+        }
+
+        entry_size.y += height;
     }
 
     stdout.flush()?;
@@ -3,7 +3,7 @@ use super::{common::*, widgets::BlockWidget};
 use crate::help;
 
 pub async fn draw_help<W: Write>(stdout: &mut W) -> Result<(), RSError> {
-    let (w, h) = crossterm::terminal::size()?;
+    let (w, h) = terminal_size()?;
 
     let lines = help::generate();
     let (mut width, lines) = help::help_lines_to_strings(&lines, w - 4)?;
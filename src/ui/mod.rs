@@ -2,7 +2,12 @@ mod common;
 mod entries;
 mod help;
 pub mod page;
+mod scenes;
+#[cfg(test)]
+mod tests;
 pub mod util;
+#[cfg(test)]
+pub mod virtual_terminal;
 pub mod widgets;
 
 use common::*;
@@ -11,12 +16,12 @@ use help::draw_help;
 pub use page::{draw_disconnected_page, draw_page};
 use util::terminal_too_small;
 pub use util::{clean_terminal, prepare_terminal, Rect};
-use widgets::{ContextMenuWidget, VolumeWidget};
+use widgets::ContextMenuWidget;
 
 use crate::models::{RedrawType, UIMode};
 
 pub async fn redraw<W: Write>(stdout: &mut W, state: &mut RSState) -> Result<(), RSError> {
-    let (w, h) = crossterm::terminal::size()?;
+    let (w, h) = terminal_size()?;
     if w < 20 || h < 5 {
         return terminal_too_small(stdout).await;
     }
@@ -44,28 +49,23 @@ pub async fn redraw<W: Write>(stdout: &mut W, state: &mut RSState) -> Result<(),
                 return draw_page(stdout, state).await;
             }
         }
-        RedrawType::PeakVolume(ident) => {
-            if ident.entry_type == EntryType::Card {
-                return Ok(());
-            }
-            if let Some(index) = state.page_entries.iter_entries().position(|p| *p == *ident) {
+        RedrawType::PeakVolumes(idents) => {
+            for (index, ident) in state.page_entries.iter_entries().enumerate() {
+                if !idents.contains(ident) || ident.entry_type == EntryType::Card {
+                    continue;
+                }
                 if let Some(mut area) = state.page_entries.is_entry_visible(index, state.scroll)? {
                     area.y += 2;
                     area.height = 1;
                     area.width -= 1;
 
-                    let ent = match state.entries.get_mut(&ident) {
+                    let play = match state.entries.get(ident).and_then(|e| e.play_entry.as_ref()) {
                         Some(x) => x,
-                        None => {
-                            return Ok(());
-                        }
+                        None => continue,
                     };
 
                     let area = Entry::calc_area(state.page_entries.lvls[index], area);
-                    let play = ent.play_entry.as_mut().unwrap();
-
-                    let vol = VolumeWidget::default().volume(play.peak);
-                    return vol.mute(play.mute).render(area, stdout);
+                    play.render_peaks(area, stdout)?;
                 }
             }
         }
@@ -77,7 +77,7 @@ pub async fn redraw<W: Write>(stdout: &mut W, state: &mut RSState) -> Result<(),
             return draw_entries(stdout, state, state.ui_page.inner_area, None).await;
         }
         RedrawType::ContextMenu => {
-            let (w, h) = crossterm::terminal::size()?;
+            let (w, h) = terminal_size()?;
             let mut b = ContextMenuWidget::new(state.page_entries.get(state.selected).unwrap())
                 .selected(state.selected_context)
                 .options(state.context_options.clone());
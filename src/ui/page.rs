@@ -1,20 +1,47 @@
 use super::{common::*, draw_entries, widgets::BlockWidget};
 
-use crate::draw_at;
+use crate::{
+    draw_at,
+    models::{PageType, UIMode},
+};
 
 pub struct UIPage {
     pub inner_area: Rect,
 }
 
 pub async fn draw_page<W: Write>(stdout: &mut W, state: &mut RSState) -> Result<(), RSError> {
-    let (w, h) = crossterm::terminal::size()?;
+    let (w, h) = terminal_size()?;
 
+    let title_len = PageType::title_len();
     let mut b = BlockWidget::default()
         .clean_inside(true)
-        .title_len(22)
+        .title_len(title_len)
         .title(state.current_page.as_styled_string());
     b.render(Rect::new(0, 0, w, h), stdout)?;
 
+    let (toggle, label) = ducking_toggle(state, w);
+    if toggle.x > title_len + 2 {
+        let style = if state.ducking.active {
+            get_style("inverted")
+        } else if state.ducking.is_enabled() {
+            get_style("normal")
+        } else {
+            get_style("muted")
+        };
+        draw_at!(stdout, label, toggle.x, 0, style);
+    }
+
+    // the search query stays in the header for as long as it filters the page
+    let typing = state.ui_mode == UIMode::Search;
+    let space = toggle.x.saturating_sub(title_len + 3) as usize;
+    if (typing || !state.search.is_empty()) && space > 2 {
+        let chip = format!(" /{}{} ", state.search, if typing { "_" } else { "" })
+            .chars()
+            .take(space)
+            .collect::<String>();
+        draw_at!(stdout, chip, title_len + 2, 0, get_style("inverted"));
+    }
+
     draw_entries(stdout, state, state.ui_page.inner_area, None).await?;
 
     stdout.flush()?;
@@ -22,8 +49,22 @@ pub async fn draw_page<W: Write>(stdout: &mut W, state: &mut RSState) -> Result<
     Ok(())
 }
 
+// right side of the header, clicking it toggles ducking
+pub fn ducking_toggle(state: &RSState, w: u16) -> (Rect, &'static str) {
+    let label = if !state.ducking.is_enabled() {
+        " ducking off "
+    } else if state.ducking.active {
+        " ducking "
+    } else {
+        " ducking on "
+    };
+    let width = label.len() as u16;
+
+    (Rect::new(w.saturating_sub(width + 2), 0, width, 1), label)
+}
+
 pub async fn draw_disconnected_page<W: Write>(stdout: &mut W, time: u64) -> Result<(), RSError> {
-    let (w, h) = crossterm::terminal::size()?;
+    let (w, h) = terminal_size()?;
 
     draw_rect!(stdout, " ", Rect::new(0, 0, w, h), get_style("normal"));
     draw_at!(
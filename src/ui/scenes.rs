@@ -0,0 +1,73 @@
+use super::common::*;
+
+use crate::draw_at;
+
+use std::collections::HashSet;
+
+use unicode_width::UnicodeWidthStr;
+
+pub async fn draw_scenes<W: Write>(
+    stdout: &mut W,
+    state: &RSState,
+    area: Rect,
+    affected: Option<HashSet<usize>>,
+) -> Result<(), RSError> {
+    if affected.is_none() {
+        draw_rect!(stdout, " ", area, get_style("normal"));
+    }
+
+    if state.scenes.is_empty() {
+        let hint = "No scenes yet, save one with `rsmixer save-scene NAME`";
+        draw_at!(
+            stdout,
+            hint.chars().take(area.width as usize).collect::<String>(),
+            area.x,
+            area.y,
+            get_style("muted")
+        );
+        stdout.flush()?;
+        return Ok(());
+    }
+
+    // keep the selected scene on the screen
+    let skip = (state.selected_scene + 1).saturating_sub(area.height as usize);
+
+    for (i, (name, scene)) in state
+        .scenes
+        .iter()
+        .enumerate()
+        .skip(skip)
+        .take(area.height as usize)
+    {
+        if affected.as_ref().is_some_and(|a| !a.contains(&i)) {
+            continue;
+        }
+
+        let details = format!("  {} entries", scene.entries.len());
+        let mut line = name.chars().take(area.width as usize).collect::<String>();
+        let y = area.y + (i - skip) as u16;
+
+        let style = if i == state.selected_scene {
+            get_style("inverted")
+        } else {
+            get_style("normal")
+        };
+        let padding = (area.width as usize).saturating_sub(line.width() + details.width());
+        line.push_str(&" ".repeat(padding));
+        draw_at!(stdout, line.clone(), area.x, y, style);
+
+        if line.width() + details.width() <= area.width as usize {
+            draw_at!(
+                stdout,
+                details,
+                area.x + line.width() as u16,
+                y,
+                get_style("muted")
+            );
+        }
+    }
+
+    stdout.flush()?;
+
+    Ok(())
+}
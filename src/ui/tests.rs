@@ -0,0 +1,224 @@
+use super::{
+    redraw,
+    util::terminal_size,
+    virtual_terminal::{assert_snapshot, VirtualTerminal},
+};
+
+use crate::{
+    config::scenes,
+    entry::{EntryIdentifier, EntryType},
+    event_loop::harness::Harness,
+    models::{PageType, RedrawType, Scene},
+    pa::fake::FakeBackend,
+    Action,
+};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+fn backend() -> FakeBackend {
+    FakeBackend::default()
+        .sink(0, "Speakers", 50)
+        .sink_input(5, "Firefox", 0, 80)
+        .sink_input(6, "Music Player", 0, 35)
+        .sink(1, "Headphones", 100)
+        .source(0, "Microphone", 100)
+        .source_output(7, "Recorder", 0, 60)
+        .card(
+            0,
+            "Built-in Audio",
+            &["output:analog-stereo", "output:hdmi-stereo", "off"],
+            0,
+        )
+        .card(1, "USB Headset", &["output:analog-stereo", "off"], 1)
+        .unavailable_profile(0, "output:hdmi-stereo")
+        .default_sink(0)
+        .default_source(0)
+}
+
+// draws the whole screen like after a resize, and then
+// whatever the last handled action asked to redraw on top of it
+async fn draw(h: &mut Harness) -> VirtualTerminal {
+    let (w, h_) = terminal_size().unwrap();
+    let mut terminal = VirtualTerminal::new(w, h_);
+
+    let requested = h.state.redraw.clone();
+
+    h.state.redraw = RedrawType::Full;
+    redraw(&mut terminal, &mut h.state).await.unwrap();
+
+    if requested != RedrawType::Full && requested != RedrawType::None {
+        h.state.redraw = requested;
+        redraw(&mut terminal, &mut h.state).await.unwrap();
+    }
+
+    terminal
+}
+
+#[tokio::test]
+async fn output_page() {
+    let mut h = Harness::new(backend(), 80, 24).await;
+
+    assert_snapshot("output_page", &draw(&mut h).await);
+}
+
+#[tokio::test]
+async fn output_page_narrow() {
+    let mut h = Harness::new(backend(), 30, 24).await;
+
+    assert_snapshot("output_page_narrow", &draw(&mut h).await);
+}
+
+#[tokio::test]
+async fn output_page_muted_and_peaks() {
+    let mut h = Harness::new(backend(), 80, 24).await;
+
+    h.send(Action::MoveDown(1)).await;
+    h.send(Action::RequestMute).await;
+    h.backend
+        .peak(EntryIdentifier::new(EntryType::Sink, 0), 0.5);
+    h.settle().await;
+
+    assert_snapshot("output_page_muted_and_peaks", &draw(&mut h).await);
+}
+
+#[tokio::test]
+async fn peak_hold_and_clip() {
+    let mut h = Harness::new(backend(), 80, 24).await;
+    let sink = EntryIdentifier::new(EntryType::Sink, 0);
+
+    h.backend.peak(sink, 1.0);
+    h.settle().await;
+    h.backend.peak(sink, 0.25);
+    h.settle().await;
+
+    assert_snapshot("peak_hold_and_clip", &draw(&mut h).await);
+}
+
+#[tokio::test]
+async fn channel_peaks() {
+    let mut h = Harness::new(backend(), 80, 24).await;
+
+    h.backend
+        .channel_peaks(EntryIdentifier::new(EntryType::Sink, 0), &[0.8, 0.2]);
+    h.settle().await;
+
+    assert_snapshot("channel_peaks", &draw(&mut h).await);
+}
+
+#[tokio::test]
+async fn input_page() {
+    let mut h = Harness::new(backend(), 80, 24).await;
+
+    h.send(Action::ChangePage(PageType::Input)).await;
+
+    assert_snapshot("input_page", &draw(&mut h).await);
+}
+
+#[tokio::test]
+async fn cards_page() {
+    let mut h = Harness::new(backend(), 80, 24).await;
+
+    h.send(Action::ChangePage(PageType::Cards)).await;
+
+    assert_snapshot("cards_page", &draw(&mut h).await);
+}
+
+#[tokio::test]
+async fn expanded_channels() {
+    let mut h = Harness::new(backend(), 80, 24).await;
+
+    h.send(Action::ToggleChannels).await;
+
+    assert_snapshot("expanded_channels", &draw(&mut h).await);
+}
+
+#[tokio::test]
+async fn context_menu() {
+    let mut h = Harness::new(backend(), 80, 24).await;
+
+    h.send(Action::MoveDown(1)).await;
+    h.send(Action::OpenContextMenu).await;
+
+    assert_snapshot("context_menu", &draw(&mut h).await);
+}
+
+#[tokio::test]
+async fn port_context_menu() {
+    let sink = EntryIdentifier::new(EntryType::Sink, 0);
+    let backend = backend().ports(sink, &["Line Out", "Headphones"]);
+    let mut h = Harness::new(backend, 80, 24).await;
+
+    h.send(Action::OpenContextMenu).await;
+
+    assert_snapshot("port_context_menu", &draw(&mut h).await);
+}
+
+#[tokio::test]
+async fn card_context_menu() {
+    let mut h = Harness::new(backend(), 80, 24).await;
+
+    h.send(Action::ChangePage(PageType::Cards)).await;
+    h.send(Action::OpenContextMenu).await;
+
+    assert_snapshot("card_context_menu", &draw(&mut h).await);
+}
+
+#[tokio::test]
+async fn unavailable_active_profile_is_greyed_out() {
+    let backend = backend().unavailable_profile(0, "output:analog-stereo");
+    let mut h = Harness::new(backend, 80, 24).await;
+
+    h.send(Action::ChangePage(PageType::Cards)).await;
+    h.send(Action::OpenContextMenu).await;
+
+    assert_snapshot("unavailable_active_profile", &draw(&mut h).await);
+}
+
+#[tokio::test]
+async fn help() {
+    let mut h = Harness::new(backend(), 80, 24).await;
+
+    h.send(Action::ShowHelp).await;
+
+    assert_snapshot("help", &draw(&mut h).await);
+}
+
+#[tokio::test]
+async fn terminal_too_small() {
+    let mut h = Harness::new(backend(), 15, 4).await;
+
+    assert_snapshot("terminal_too_small", &draw(&mut h).await);
+}
+
+#[tokio::test]
+async fn search_filter() {
+    let mut h = Harness::new(backend(), 80, 24).await;
+
+    h.send(Action::StartSearch).await;
+    for c in "o".chars() {
+        h.send(Action::KeyPress(KeyEvent::new(
+            KeyCode::Char(c),
+            KeyModifiers::NONE,
+        )))
+        .await;
+    }
+
+    assert_snapshot("search_filter", &draw(&mut h).await);
+}
+
+#[tokio::test]
+async fn scenes_page() {
+    let mut h = Harness::new(backend(), 80, 24).await;
+
+    h.send(Action::ChangePage(PageType::Scenes)).await;
+    h.state.scenes.clear();
+    for name in &["evening", "calls", "gaming"] {
+        h.state
+            .scenes
+            .insert(name.to_string(), Scene::capture(&h.state));
+    }
+    h.send(Action::MoveDown(1)).await;
+
+    assert_snapshot("scenes_page", &draw(&mut h).await);
+    scenes::remove();
+}
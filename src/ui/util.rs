@@ -1,7 +1,7 @@
 use crate::{
     entry::{EntrySpaceLvl, EntryType},
     models::PageType,
-    RSError, STYLES,
+    RSError, STYLES, VARIABLES,
 };
 
 use crossterm::style::{Attribute, ContentStyle};
@@ -10,12 +10,40 @@ use lazy_static::lazy_static;
 
 use std::{io, io::Write};
 
-use crossterm::{cursor::Hide, execute};
+#[cfg(test)]
+use std::cell::Cell;
+
+use crossterm::{
+    cursor::Hide,
+    event::{DisableMouseCapture, EnableMouseCapture},
+    execute,
+};
 
 lazy_static! {
     pub static ref Y_PADDING: u16 = 4;
 }
 
+// tests draw into a virtual terminal, so they pick its size themselves
+#[cfg(test)]
+thread_local! {
+    static TEST_TERMINAL_SIZE: Cell<(u16, u16)> = const { Cell::new((80, 24)) };
+}
+
+#[cfg(test)]
+pub fn set_terminal_size(w: u16, h: u16) {
+    TEST_TERMINAL_SIZE.with(|size| size.set((w, h)));
+}
+
+#[cfg(not(test))]
+pub fn terminal_size() -> Result<(u16, u16), RSError> {
+    Ok(crossterm::terminal::size()?)
+}
+
+#[cfg(test)]
+pub fn terminal_size() -> Result<(u16, u16), RSError> {
+    Ok(TEST_TERMINAL_SIZE.with(|size| size.get()))
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Rect {
     pub x: u16,
@@ -53,6 +81,9 @@ impl Rect {
     pub fn h(&self, h: u16) -> Self {
         Self::new(self.x, self.y, self.width, h)
     }
+    pub fn contains(&self, x: u16, y: u16) -> bool {
+        x >= self.x && x < self.x + self.width && y >= self.y && y < self.y + self.height
+    }
 }
 
 pub fn get_style(name: &'static str) -> ContentStyle {
@@ -89,7 +120,7 @@ pub fn parent_child_types(page: PageType) -> (EntryType, EntryType) {
     match page {
         PageType::Output => (EntryType::Sink, EntryType::SinkInput),
         PageType::Input => (EntryType::Source, EntryType::SourceOutput),
-        PageType::Cards => (EntryType::Card, EntryType::Card),
+        PageType::Cards | PageType::Scenes => (EntryType::Card, EntryType::Card),
     }
 }
 
@@ -98,12 +129,18 @@ pub fn prepare_terminal() -> Result<io::Stdout, RSError> {
     crossterm::execute!(stdout, crossterm::terminal::EnterAlternateScreen)?;
     crossterm::terminal::enable_raw_mode()?;
     execute!(stdout, Hide)?;
+    if VARIABLES.get().mouse {
+        execute!(stdout, EnableMouseCapture)?;
+    }
 
     Ok(stdout)
 }
 
 pub fn clean_terminal() -> Result<(), RSError> {
     let mut stdout = std::io::stdout();
+    if VARIABLES.get().mouse {
+        execute!(stdout, DisableMouseCapture)?;
+    }
     crossterm::execute!(
         stdout,
         crossterm::cursor::Show,
@@ -115,7 +152,7 @@ pub fn clean_terminal() -> Result<(), RSError> {
 }
 
 pub async fn terminal_too_small<W: Write>(stdout: &mut W) -> Result<(), RSError> {
-    let (w, h) = crossterm::terminal::size()?;
+    let (w, h) = terminal_size()?;
     execute!(stdout, crossterm::cursor::MoveTo(0, 0))?;
     let x = get_style("normal").apply(format!(
         "terminal too small{}",
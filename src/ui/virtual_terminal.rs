@@ -0,0 +1,299 @@
+use std::{fs, io, io::Write, path::PathBuf};
+
+// In-memory terminal for renderer tests. It understands the escape sequences
+// crossterm emits while drawing (cursor movement, colors, attributes and clearing),
+// everything else is ignored
+pub struct VirtualTerminal {
+    width: u16,
+    height: u16,
+    bytes: Vec<u8>,
+}
+
+#[derive(Clone, Default, PartialEq)]
+struct Style {
+    fg: Option<String>,
+    bg: Option<String>,
+    attributes: Vec<String>,
+}
+
+impl Style {
+    fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(fg) = &self.fg {
+            parts.push(format!("fg={}", fg));
+        }
+        if let Some(bg) = &self.bg {
+            parts.push(format!("bg={}", bg));
+        }
+        parts.extend(self.attributes.iter().cloned());
+        parts.join(" ")
+    }
+
+    fn apply_sgr(&mut self, params: &str) {
+        let codes = params
+            .split(';')
+            .map(|c| c.parse::<u16>().unwrap_or(0))
+            .collect::<Vec<_>>();
+
+        let mut i = 0;
+        while i < codes.len() {
+            match codes[i] {
+                0 => *self = Style::default(),
+                38 | 48 => {
+                    let (color, used) = match codes.get(i + 1) {
+                        Some(5) => (codes.get(i + 2).map(|c| color_name(*c)), 3),
+                        Some(2) if i + 4 < codes.len() => (
+                            Some(format!(
+                                "#{:02x}{:02x}{:02x}",
+                                codes[i + 2],
+                                codes[i + 3],
+                                codes[i + 4]
+                            )),
+                            5,
+                        ),
+                        _ => (None, 1),
+                    };
+                    if codes[i] == 38 {
+                        self.fg = color;
+                    } else {
+                        self.bg = color;
+                    }
+                    i += used;
+                    continue;
+                }
+                39 => self.fg = None,
+                49 => self.bg = None,
+                22 => self.attributes.retain(|a| a != "bold" && a != "dim"),
+                23 => self.attributes.retain(|a| a != "italic"),
+                24 => self.attributes.retain(|a| a != "underlined"),
+                27 => self.attributes.retain(|a| a != "reverse"),
+                code => {
+                    let attribute = match code {
+                        1 => "bold",
+                        2 => "dim",
+                        3 => "italic",
+                        4 => "underlined",
+                        7 => "reverse",
+                        _ => "unknown",
+                    }
+                    .to_string();
+                    if !self.attributes.contains(&attribute) {
+                        self.attributes.push(attribute);
+                    }
+                }
+            }
+            i += 1;
+        }
+    }
+}
+
+// names used by crossterm for the 16 basic colors
+fn color_name(code: u16) -> String {
+    let names = [
+        "black",
+        "dark_red",
+        "dark_green",
+        "dark_yellow",
+        "dark_blue",
+        "dark_magenta",
+        "dark_cyan",
+        "grey",
+        "dark_grey",
+        "red",
+        "green",
+        "yellow",
+        "blue",
+        "magenta",
+        "cyan",
+        "white",
+    ];
+    match names.get(code as usize) {
+        Some(name) => name.to_string(),
+        None => code.to_string(),
+    }
+}
+
+#[derive(Clone)]
+struct Cell {
+    ch: char,
+    style: Style,
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self {
+            ch: ' ',
+            style: Style::default(),
+        }
+    }
+}
+
+impl VirtualTerminal {
+    pub fn new(width: u16, height: u16) -> Self {
+        Self {
+            width,
+            height,
+            bytes: Vec::new(),
+        }
+    }
+
+    // replays everything written so far onto an empty screen
+    fn cells(&self) -> Vec<Vec<Cell>> {
+        let (w, h) = (self.width as usize, self.height as usize);
+        let mut cells = vec![vec![Cell::default(); w]; h];
+        let mut style = Style::default();
+        let (mut x, mut y) = (0usize, 0usize);
+
+        let text = String::from_utf8_lossy(&self.bytes);
+        let mut chars = text.chars().peekable();
+
+        while let Some(ch) = chars.next() {
+            match ch {
+                '\x1b' => {
+                    if chars.peek() != Some(&'[') {
+                        chars.next();
+                        continue;
+                    }
+                    chars.next();
+
+                    let mut params = String::new();
+                    let mut command = None;
+                    for c in &mut chars {
+                        if ('\x40'..='\x7e').contains(&c) {
+                            command = Some(c);
+                            break;
+                        }
+                        params.push(c);
+                    }
+
+                    // private modes, like hiding the cursor
+                    if params.starts_with('?') {
+                        continue;
+                    }
+
+                    match command {
+                        Some('H') => {
+                            let mut pos =
+                                params.split(';').map(|p| p.parse::<usize>().unwrap_or(1));
+                            y = pos.next().unwrap_or(1).saturating_sub(1);
+                            x = pos.next().unwrap_or(1).saturating_sub(1);
+                        }
+                        Some('m') => style.apply_sgr(&params),
+                        Some('J') if params == "2" => {
+                            cells = vec![vec![Cell::default(); w]; h];
+                        }
+                        Some('K') if y < h => {
+                            for cell in cells[y].iter_mut().skip(x) {
+                                *cell = Cell::default();
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                '\r' => x = 0,
+                '\n' => y += 1,
+                _ => {
+                    if x >= w {
+                        x = 0;
+                        y += 1;
+                    }
+                    if y < h {
+                        cells[y][x] = Cell {
+                            ch,
+                            style: style.clone(),
+                        };
+                    }
+                    x += 1;
+                }
+            }
+        }
+
+        cells
+    }
+
+    // screen contents followed by a map of styles, one letter per distinct style
+    pub fn snapshot(&self) -> String {
+        let cells = self.cells();
+        let border = format!("+{}+\n", "-".repeat(self.width as usize));
+
+        let mut out = format!("{}x{}\n", self.width, self.height);
+        out.push_str(&border);
+        for row in &cells {
+            out.push('|');
+            out.extend(row.iter().map(|c| c.ch));
+            out.push_str("|\n");
+        }
+        out.push_str(&border);
+
+        let mut styles: Vec<Style> = Vec::new();
+
+        out.push('\n');
+        out.push_str(&border);
+        for row in &cells {
+            out.push('|');
+            for cell in row {
+                if cell.style == Style::default() {
+                    out.push('.');
+                    continue;
+                }
+                let index = match styles.iter().position(|s| *s == cell.style) {
+                    Some(i) => i,
+                    None => {
+                        styles.push(cell.style.clone());
+                        styles.len() - 1
+                    }
+                };
+                out.push(style_letter(index));
+            }
+            out.push_str("|\n");
+        }
+        out.push_str(&border);
+
+        for (i, style) in styles.iter().enumerate() {
+            out.push_str(&format!("{}: {}\n", style_letter(i), style.describe()));
+        }
+
+        out
+    }
+}
+
+fn style_letter(index: usize) -> char {
+    let letters = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+    letters.chars().nth(index).unwrap_or('?')
+}
+
+impl Write for VirtualTerminal {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.bytes.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+// Compares the screen with src/ui/snapshots/<name>.snap. Missing snapshots are
+// written, UPDATE_SNAPSHOTS=1 rewrites all of them
+pub fn assert_snapshot(name: &str, terminal: &VirtualTerminal) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("src/ui/snapshots")
+        .join(format!("{}.snap", name));
+    let actual = terminal.snapshot();
+
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() || !path.exists() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, &actual).unwrap();
+        return;
+    }
+
+    let expected = fs::read_to_string(&path).unwrap();
+    assert!(
+        expected == actual,
+        "snapshot {} doesn't match, run with UPDATE_SNAPSHOTS=1 if the change is intended\n\
+         expected:\n{}\nactual:\n{}",
+        name,
+        expected,
+        actual
+    );
+}
@@ -8,7 +8,10 @@ use crate::{
     RSError,
 };
 
-use std::{cmp::max, io::Write};
+use std::{
+    cmp::{max, min},
+    io::Write,
+};
 
 use crossterm::execute;
 
@@ -39,10 +42,9 @@ impl ContextMenuWidget {
         self.options = options;
         self
     }
-}
 
-impl<W: Write> Widget<W> for ContextMenuWidget {
-    fn render(&mut self, mut area: Rect, buf: &mut W) -> Result<(), RSError> {
+    // the part of area taken by the menu, centered and only as high as the options need
+    pub fn frame(&mut self, mut area: Rect) -> Rect {
         let recommended_h = self.options.len() + 6;
         if recommended_h < area.height as usize {
             self.scrolling = false;
@@ -63,28 +65,53 @@ impl<W: Write> Widget<W> for ContextMenuWidget {
             area.width = target_w;
         }
 
+        area
+    }
+
+    // index of the option drawn in row y, when the menu is rendered in area
+    pub fn option_at(&mut self, area: Rect, y: u16) -> Option<usize> {
+        let area = self.frame(area);
+        let (first, count) = self.visible_options(area);
+
+        if y < area.y + 3 || y >= area.y + 3 + count as u16 {
+            return None;
+        }
+        Some(first + (y - area.y - 3) as usize)
+    }
+
+    // first option shown and how many of them fit
+    fn visible_options(&self, area: Rect) -> (usize, usize) {
+        if self.scrolling {
+            let start_index = max(0, self.selected as i32 - area.height as i32 + 7) as usize;
+            let count = min(
+                area.height as usize - 6,
+                self.options.len().saturating_sub(start_index),
+            );
+            (start_index, count)
+        } else {
+            (0, self.options.len())
+        }
+    }
+}
+
+impl<W: Write> Widget<W> for ContextMenuWidget {
+    fn render(&mut self, area: Rect, buf: &mut W) -> Result<(), RSError> {
+        let area = self.frame(area);
+
         let mut b = BlockWidget::default().clean_inside(true);
         b.render(area, buf)?;
 
         let mut starty = area.y + 3;
 
-        let start_index = max(0, self.selected as i32 - area.height as i32 + 7);
+        let (start_index, count) = self.visible_options(area);
 
-        let iter = if self.scrolling {
-            self.options
-                .iter()
-                .enumerate()
-                .skip(start_index as usize)
-                .take(area.height as usize - 6)
-        } else {
-            self.options
-                .iter()
-                .enumerate()
-                .skip(0)
-                .take(self.options.len())
-        };
-
-        for (i, o) in iter {
+        for (i, o) in self
+            .options
+            .iter()
+            .enumerate()
+            .skip(start_index)
+            .take(count)
+        {
             let s: String = o.clone().into();
             let startx = area.x + area.width / 2 - s.len() as u16 / 2;
             draw_at!(
@@ -94,6 +121,8 @@ impl<W: Write> Widget<W> for ContextMenuWidget {
                 starty,
                 if self.selected == i {
                     get_style("inverted")
+                } else if !o.is_available() {
+                    get_style("muted")
                 } else {
                     get_style("normal")
                 }
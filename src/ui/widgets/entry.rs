@@ -1,16 +1,22 @@
 use crate::{
     draw_at,
-    entry::{Entry, EntrySpaceLvl, EntryType, HiddenStatus},
+    entry::{
+        volume_to_percent, Entry, EntrySpaceLvl, EntryType, HiddenStatus, PeakMeter, PlayEntry,
+        MAX_PERCENT,
+    },
+    repeat_string,
     ui::{
         util::{get_style, Rect},
-        widgets::{VolumeWidgetBorder, Widget},
+        widgets::{VolumeWidget, VolumeWidgetBorder, Widget},
     },
     RSError,
 };
 
 use std::{cmp::min, io::Write};
 
-use pulse::volume;
+use pulse::channelmap::Position;
+
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 use crossterm::{cursor::MoveTo, execute};
 
@@ -36,6 +42,8 @@ impl Entry {
         let style = get_style(style);
         let name_style = if self.is_selected {
             "inverted"
+        } else if self.is_match {
+            "orange"
         } else {
             "normal"
         };
@@ -73,51 +81,36 @@ impl Entry {
             "normal"
         };
         let style = get_style(style);
-        let name_style = if self.is_selected {
+        let name_style = if self.is_selected && play.selected_channel.is_none() {
             "inverted"
+        } else if self.is_selected {
+            "normal.bold"
+        } else if self.is_match {
+            "orange"
+        } else if self.is_default {
+            "green"
         } else {
             "normal"
         };
         let name_style = get_style(name_style);
 
-        let area1: Rect;
-        let mut area2: Rect;
-
-        let a = area;
-        let mut area_a = Entry::calc_area(self.position, a);
+        let mut area_a = Entry::calc_area(self.position, area);
         let small = area_a.width <= 35;
+        let (area1, mut area2) = split_play_area(area_a);
 
-        let seventy_percent = (area_a.width as f32 * 0.7).floor() as u16;
-        if area_a.width > 30 + seventy_percent {
-            area1 = Rect::new(area_a.x, area_a.y, area_a.width - seventy_percent, 1);
-            area2 = Rect::new(
-                area_a.x + area_a.width - seventy_percent + 1,
-                area_a.y,
-                seventy_percent - 2,
-                1,
-            );
-        } else if area_a.width > 35 {
-            area1 = Rect::new(area_a.x, area_a.y, 35, 1);
-            area2 = Rect::new(area_a.x + 36, area_a.y, area_a.width - 37, 1);
-        } else {
-            area1 = Rect::new(area_a.x, area_a.y, area_a.width, 1);
-            area2 = Rect::new(0, 0, 0, 0);
-        }
-
-        let avg = play.volume.avg().0;
-        let base_delta = (volume::Volume::NORMAL.0 as f32 - volume::Volume::MUTED.0 as f32) / 100.0;
-        let vol_percent = ((avg - volume::Volume::MUTED.0) as f32 / base_delta).round() as u32;
-        let main_vol = vol_percent as f32 / 150.0;
+        let vol_percent = volume_to_percent(play.volume.max());
+        let main_vol = vol_percent as f32 / MAX_PERCENT as f32;
 
         play.volume_bar = play
             .volume_bar
             .volume(main_vol)
             .mute(play.mute)
             .border(VolumeWidgetBorder::Upper);
-        play.peak_volume_bar = play.peak_volume_bar.mute(play.mute).volume(play.peak);
-
-        let short_name = self
-            .name
+        let name = match play.active_port {
+            Some(i) => format!("{} ({})", self.name, play.ports[i].description),
+            None => self.name.clone(),
+        };
+        let short_name = name
             .chars()
             .take(if area1.width > 2 {
                 area1.width as usize - 2
@@ -131,7 +124,7 @@ impl Entry {
 
         let vol_perc = format!("  {}", vol_percent);
         let vol_perc = String::from(&vol_perc[vol_perc.len() - 3..vol_perc.len()]);
-        let vol_db = play.volume.avg().print_db();
+        let vol_db = play.volume.max().print_db();
         if vol_db.len() + vol_perc.len() <= area1.width as usize + 3 {
             let vol_str = format!(
                 "{}{}{}",
@@ -175,6 +168,15 @@ impl Entry {
             _ => {}
         };
 
+        let channel_rows = if play.expanded {
+            play.channel_rows()
+        } else {
+            0
+        };
+        if self.position == EntrySpaceLvl::Parent || self.position == EntrySpaceLvl::MidChild {
+            v.resize(v.len() + channel_rows, "│");
+        }
+
         for (i, q) in v.iter().enumerate() {
             execute!(buf, MoveTo(area.x, area.y + i as u16))?;
             write!(buf, "{}", style.clone().apply(q))?;
@@ -186,7 +188,7 @@ impl Entry {
             draw_at!(buf, c, area2.x + area2.width, area2.y, style.clone());
             draw_at!(buf, c, area2.x - 1, area2.y, style.clone());
             draw_at!(buf, c, area2.x + area2.width, area2.y + 1, style.clone());
-            draw_at!(buf, c, area2.x - 1, area2.y + 1, style);
+            draw_at!(buf, c, area2.x - 1, area2.y + 1, style.clone());
 
             play.volume_bar.render(area2, buf)?;
             area2.y += 1;
@@ -202,10 +204,235 @@ impl Entry {
         } else {
             area_a.width = 0;
         }
-        play.peak_volume_bar.render(area_a, buf)?;
+        play.render_peaks(area_a, buf)?;
+
+        if channel_rows > 0 {
+            let mut row1 = area1;
+            let mut row2 = area2;
+
+            for (c, vol) in play.volume.get().iter().enumerate() {
+                row1.y = area_a.y + 1 + c as u16;
+                row2.y = row1.y;
+
+                let percent = volume_to_percent(*vol);
+                let label = match play.channel_map.get().get(c) {
+                    Some(&pos) => Position::to_pretty_string(pos).unwrap_or_default(),
+                    None => String::new(),
+                };
+
+                render_channel_label(
+                    buf,
+                    row1,
+                    &label,
+                    &format!("{}", percent),
+                    play.selected_channel == Some(c),
+                )?;
+
+                if !small {
+                    VolumeWidget::default()
+                        .volume(percent as f32 / MAX_PERCENT as f32)
+                        .mute(play.mute)
+                        .render(row2, buf)?;
+                }
+            }
+
+            if play.channel_map.can_balance() {
+                let c = play.volume.len() as usize;
+                row1.y = area_a.y + 1 + c as u16;
+                row2.y = row1.y;
+
+                let balance = play.volume.get_balance(&play.channel_map);
+
+                render_channel_label(
+                    buf,
+                    row1,
+                    "Balance",
+                    &format!("{:.2}", balance),
+                    play.selected_channel == Some(c),
+                )?;
+
+                if !small && row2.width > 2 {
+                    let inner = row2.width as usize - 2;
+                    let marker = ((balance + 1.0) / 2.0 * (inner - 1) as f32).round() as usize;
+                    let bar = (0..inner)
+                        .map(|i| if i == marker { "┃" } else { "─" })
+                        .collect::<String>();
+
+                    draw_at!(buf, format!("L{}R", bar), row2.x, row2.y, style.clone());
+                }
+            }
+        }
 
         buf.flush()?;
 
         Ok(())
     }
 }
+
+impl Entry {
+    // where the volume bar of a play entry drawn in area is, it takes two rows
+    // and has no width when the entry is too narrow to show it
+    pub fn volume_bar_area(position: EntrySpaceLvl, area: Rect) -> Rect {
+        let (_, bar) = split_play_area(Entry::calc_area(position, area));
+        bar.h(2)
+    }
+}
+
+// name and volume on the left, volume bar on the right
+fn split_play_area(area: Rect) -> (Rect, Rect) {
+    let seventy_percent = (area.width as f32 * 0.7).floor() as u16;
+    if area.width > 30 + seventy_percent {
+        (
+            Rect::new(area.x, area.y, area.width - seventy_percent, 1),
+            Rect::new(
+                area.x + area.width - seventy_percent + 1,
+                area.y,
+                seventy_percent - 2,
+                1,
+            ),
+        )
+    } else if area.width > 35 {
+        (
+            Rect::new(area.x, area.y, 35, 1),
+            Rect::new(area.x + 36, area.y, area.width - 37, 1),
+        )
+    } else {
+        (
+            Rect::new(area.x, area.y, area.width, 1),
+            Rect::new(0, 0, 0, 0),
+        )
+    }
+}
+
+impl PlayEntry {
+    // the peak row, either a single meter or one thin meter per channel
+    pub fn render_peaks<W: Write>(&self, area: Rect, buf: &mut W) -> Result<(), RSError> {
+        let channels = self.channel_meters.len() as u16;
+
+        // every channel needs a label and a few cells for its meter
+        if channels < 2 || area.width < channels * 8 {
+            return render_meter(&self.meter, self.mute, area, buf);
+        }
+
+        let part = area.width / channels;
+        for (c, meter) in self.channel_meters.iter().enumerate() {
+            let label = match self.channel_map.get().get(c) {
+                Some(&pos) => short_channel_name(pos, c),
+                None => format!("{}", c + 1),
+            };
+            let x = area.x + part * c as u16;
+            let width = if c as u16 == channels - 1 {
+                area.width - part * c as u16
+            } else {
+                part
+            };
+            let label_width = min(label.len() as u16, 3);
+
+            draw_at!(
+                buf,
+                &label[..label_width as usize],
+                x,
+                area.y,
+                get_style("normal")
+            );
+            render_meter(
+                meter,
+                self.mute,
+                Rect::new(x + label_width, area.y, width - label_width, 1),
+                buf,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+// the last cell of the area is left for the clip marker, right after the bar
+fn render_meter<W: Write>(
+    meter: &PeakMeter,
+    mute: bool,
+    area: Rect,
+    buf: &mut W,
+) -> Result<(), RSError> {
+    if area.width < 3 {
+        return Ok(());
+    }
+
+    VolumeWidget::default()
+        .volume(meter.level)
+        .hold(meter.hold)
+        .mute(mute)
+        .render(Rect::new(area.x, area.y, area.width - 1, 1), buf)?;
+
+    let marker = if meter.clipped { "!" } else { " " };
+    draw_at!(
+        buf,
+        marker,
+        area.x + area.width - 1,
+        area.y,
+        get_style("red")
+    );
+
+    Ok(())
+}
+
+// short enough to fit in front of a thin meter, L and R for stereo
+fn short_channel_name(pos: Position, index: usize) -> String {
+    match pos {
+        Position::Mono => "M".to_string(),
+        Position::FrontLeft => "L".to_string(),
+        Position::FrontRight => "R".to_string(),
+        Position::FrontCenter => "C".to_string(),
+        Position::RearLeft => "RL".to_string(),
+        Position::RearRight => "RR".to_string(),
+        Position::RearCenter => "RC".to_string(),
+        Position::Lfe => "LFE".to_string(),
+        Position::SideLeft => "SL".to_string(),
+        Position::SideRight => "SR".to_string(),
+        _ => format!("{}", index + 1),
+    }
+}
+
+fn render_channel_label<W: Write>(
+    buf: &mut W,
+    area: Rect,
+    label: &str,
+    value: &str,
+    selected: bool,
+) -> Result<(), RSError> {
+    if area.width < 4 {
+        return Ok(());
+    }
+
+    // labels are translated by PulseAudio, so they can have wide characters
+    let width = area.width as usize - 3;
+    let mut label_width = 0;
+    let label = label
+        .chars()
+        .take_while(|c| {
+            label_width += c.width().unwrap_or(0);
+            label_width <= width
+        })
+        .collect::<String>();
+    let value = if label.width() + value.width() < width {
+        value
+    } else {
+        ""
+    };
+    let space = repeat_string!(" ", width - label.width() - value.width());
+
+    let style = if selected {
+        get_style("inverted")
+    } else {
+        get_style("normal")
+    };
+
+    execute!(buf, MoveTo(area.x + 1, area.y))?;
+    write!(
+        buf,
+        "{}",
+        style.apply(format!("{}{}{}", label, space, value))
+    )?;
+
+    Ok(())
+}
@@ -2,10 +2,12 @@ mod block;
 mod context_menu;
 mod entry;
 mod volume;
+mod volume_input;
 
 pub use block::BlockWidget;
 pub use context_menu::ContextMenuWidget;
 pub use volume::{VolumeWidget, VolumeWidgetBorder};
+pub use volume_input::VolumeInputWidget;
 
 use super::util::Rect;
 
@@ -28,6 +28,8 @@ pub struct VolumeWidget {
     last_filled: u16,
     last_area: Rect,
     pub mute: bool,
+    // peak-hold marker position, same scale as percent
+    pub hold: f32,
 }
 
 impl VolumeWidget {
@@ -38,6 +40,7 @@ impl VolumeWidget {
             last_filled: 0,
             last_area: Rect::default(),
             mute: false,
+            hold: 0.0,
         }
     }
 
@@ -56,6 +59,11 @@ impl VolumeWidget {
         self
     }
 
+    pub fn hold(mut self, hold: f32) -> Self {
+        self.hold = hold;
+        self
+    }
+
     fn get_segments(&self, width: u16) -> (u16, u16, u16) {
         let third = (0.34 * (width - 2) as f32).floor() as u16;
         let last = width - 2 - third * 2;
@@ -140,6 +148,25 @@ impl<W: Write> Widget<W> for VolumeWidget {
             write!(buf, "{}", s)?;
         }
 
+        if area.width > 2 && self.hold > self.percent {
+            let held = min(
+                (self.hold * (area.width - 2) as f32).floor() as u16,
+                area.width - 3,
+            );
+            if held >= filled {
+                let style = if self.mute {
+                    "muted"
+                } else if held < third {
+                    "green"
+                } else if held < third * 2 {
+                    "orange"
+                } else {
+                    "red"
+                };
+                draw_at!(buf, "|", area.x + 1 + held, area.y, get_style(style));
+            }
+        }
+
         buf.flush()?;
 
         Ok(())
@@ -0,0 +1,54 @@
+use super::Widget;
+
+use crate::{
+    entry::parse_volume,
+    repeat_string,
+    ui::util::{get_style, Rect},
+    RSError,
+};
+
+use std::io::Write;
+
+use crossterm::{cursor::MoveTo, execute};
+
+#[derive(Clone)]
+pub struct VolumeInputWidget {
+    pub value: String,
+}
+
+impl VolumeInputWidget {
+    pub fn new(value: String) -> Self {
+        Self { value }
+    }
+}
+
+impl<W: Write> Widget<W> for VolumeInputWidget {
+    fn render(&mut self, area: Rect, buf: &mut W) -> Result<(), RSError> {
+        if area.width < 4 {
+            return Ok(());
+        }
+
+        // highlight text that can't be accepted yet
+        let style = if self.value.is_empty() || parse_volume(&self.value).is_some() {
+            get_style("inverted")
+        } else {
+            get_style("red")
+        };
+
+        let width = area.width as usize - 2;
+        let text = format!("volume: {}_", self.value);
+        let len = text.chars().count();
+        let text = if len > width {
+            text.chars().skip(len - width).collect::<String>()
+        } else {
+            format!("{}{}", text, repeat_string!(" ", width - len))
+        };
+
+        execute!(buf, MoveTo(area.x + 1, area.y))?;
+        write!(buf, "{}", style.apply(text))?;
+
+        buf.flush()?;
+
+        Ok(())
+    }
+}